@@ -0,0 +1,22 @@
+use snipper::{Coordinate, Point, Path, Polygon};
+
+pub type Rings = Vec<Vec<(i32, i32)>>;
+
+fn coordinate(value: i32) -> i32 {
+    value % (Coordinate::MAX + 1)
+}
+
+#[allow(dead_code)]
+pub fn paths(rings: &Rings) -> Vec<Path> {
+    rings.iter().map(|ring| {
+        let points: Vec<Point> = ring.iter().map(|(x, y)| {
+            Point::new(coordinate(*x), coordinate(*y)).expect("Coordinates wrapped into range")
+        }).collect();
+        Path::new(&points)
+    }).collect()
+}
+
+#[allow(dead_code)]
+pub fn polygon(rings: &Rings) -> Polygon {
+    unsafe { Polygon::flat(paths(rings)).expect("Flat polygon expected") }
+}
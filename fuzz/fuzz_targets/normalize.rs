@@ -0,0 +1,12 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use snipper::Snipper;
+
+mod common;
+use common::{Rings, paths};
+
+fuzz_target!(|rings: Rings| {
+    if let Ok(solution) = Snipper::normalize(paths(&rings)) {
+        let _ = solution.polygon();
+    }
+});
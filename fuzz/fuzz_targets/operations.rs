@@ -0,0 +1,21 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use snipper::Snipper;
+
+mod common;
+use common::{Rings, polygon};
+
+fuzz_target!(|input: (Rings, Rings)| {
+    let (subject, clipping) = input;
+    let operations = [
+        Snipper::union,
+        Snipper::intersection,
+        Snipper::difference,
+        Snipper::xor
+    ];
+    for operation in operations.iter() {
+        if let Ok(solution) = operation(polygon(&subject), polygon(&clipping)) {
+            let _ = solution.polygon();
+        }
+    }
+});
@@ -1,42 +1,223 @@
 use super::shape::Polygon;
 use crate::shape::{Path, Shape};
 use super::error::Error;
-use crate::operation::{Operation};
+use crate::operation::{Operation, Operand, Membership};
 use crate::intersection_algorithm::{Scope};
 use crate::{Queue, Coordinate, Bounds};
 use std::collections::btree_map::BTreeMap;
+use std::collections::HashSet;
 use crate::units::Float;
 use crate::intersection_algorithm::position::Position;
 use crate::intersection_algorithm::scope::Key;
 use crate::intersection_algorithm::ray::Ray;
 use crate::edge::queue::AbstractQueue;
-use crate::shape::polygon::{Comparator, Relation};
+use crate::shape::polygon::{Comparator, Relation, GeometricComparator};
+use crate::primitives::{self, Point};
+use crate::rectangle_clipping;
+use crate::snap_rounding;
+use crate::transform::Transform;
+use crate::intersection_matrix::{IntersectionMatrix, Dimension};
+use crate::intersection_points::IntersectionPoint;
+use crate::intersection_algorithm::IntersectionAlgorithm;
+use crate::edge::Edge;
 use crate::drawing_algorithm::drawing_algorithm::DrawingAlgorithm;
 use crate::drawing_algorithm::partition::Partition;
-use crate::drawing_algorithm::builder::BuilderIndex;
+use crate::drawing_algorithm::builder::{BuilderIndex, PathsInConstruction};
 use crate::drawing_algorithm::routes::FirstIndex;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 pub struct Solution {
-    data: Option<(Vec<Path>, PathComparator)>
+    data: Option<(Vec<Path>, Box<dyn Comparator + Send>)>,
+    canonical: bool,
+    cleanup: bool,
+    split_pinches: bool,
+    strict: bool,
+    min_area: Option<f64>,
+    min_width: Option<f64>,
+    max_thinness: Option<f64>
 }
 impl Solution {
-    fn new(data: Option<(Vec<Path>, PathComparator)>) -> Solution {
-        Solution { data }
+    fn new(data: Option<(Vec<Path>, Box<dyn Comparator + Send>)>) -> Solution {
+        Solution {
+            data,
+            canonical: false,
+            cleanup: true,
+            split_pinches: false,
+            strict: false,
+            min_area: None,
+            min_width: None,
+            max_thinness: None
+        }
+    }
+    // Paths of operands apart from each other, nested each in their own
+    fn joined(first: Solution, second: Solution) -> Solution {
+        match (first.data, second.data) {
+            (Some((mut paths, first)), Some((mut more, second))) => {
+                let split = paths.len();
+                paths.append(&mut more);
+                Solution::new(Some((paths, Box::new(JoinedComparator { first, second, split }))))
+            },
+            (data, None) | (None, data) => Solution::new(data)
+        }
+    }
+    pub fn canonical(mut self) -> Solution {
+        self.canonical = true;
+        self
+    }
+    // Keeps the paths as they were drawn, with collinear points
+    // where split edges meet again
+    pub fn without_cleanup(mut self) -> Solution {
+        self.cleanup = false;
+        self
+    }
+    // Paths touching themselves at a point are split into separate
+    // rings there, as OGC simple features require
+    pub fn split_pinches(mut self) -> Solution {
+        self.split_pinches = true;
+        self
+    }
+    // Output valid as OGC simple features: cleaned up, split at pinches,
+    // without degenerate rings, with touching rings of one part untangled
+    // and oriented by depth. What can't be made valid this way is reported
+    // as an error by the polygon
+    pub fn strict(mut self) -> Solution {
+        self.cleanup = true;
+        self.split_pinches = true;
+        self.strict = true;
+        self
+    }
+    // Parts and gaps between parts of smaller area than given are dropped
+    // or filled, along with all their holes
+    pub fn min_area(mut self, area: f64) -> Solution {
+        self.min_area = Some(area);
+        self
+    }
+    // Same for parts and gaps nowhere as wide as given
+    pub fn min_width(mut self, width: f64) -> Solution {
+        self.min_width = Some(width);
+        self
     }
-    pub fn paths(self) -> Vec<Path> {
-        if let Some((paths, _)) = self.data {
+    // Same for parts and gaps thinner than given, thinness being the squared
+    // perimeter over the area times 4π, one for a circle
+    pub fn max_thinness(mut self, ratio: f64) -> Solution {
+        self.max_thinness = Some(ratio);
+        self
+    }
+    fn is_sliver(&self, face: &Polygon) -> bool {
+        self.min_area.is_some_and(|area| face.area() < area) ||
+            self.max_thinness.is_some_and(|ratio| face.compactness() * ratio < 1f64) ||
+            self.min_width.is_some_and(|width| face.is_narrower_than(width))
+    }
+    // The sweep's nesting is kept, an affine map doesn't change it
+    pub fn transform(mut self, transform: &Transform) -> Result<Solution, Error> {
+        if let Some((paths, comparator)) = self.data.take() {
+            let paths = paths.iter().map(|path| path.transform(transform)).collect::<Result<Vec<Path>, Error>>()?;
+            self.data = Some((paths, comparator));
+        }
+        Ok(self)
+    }
+    // Vertices go to the centres of hot pixels twice the tolerance wide and edges
+    // are routed through the centres of all hot pixels they pass, over and over.
+    // No vertex then lies closer than the tolerance to an edge not incident to it.
+    // The routed paths may overlap, they are normalized again
+    pub fn snap_rounded(mut self, tolerance: u32) -> Result<Solution, Error> {
+        if tolerance == 0 || tolerance > Coordinate::MAX as u32 {
+            return Err(Error::OutOfBoundsError("tolerance".to_string()));
+        }
+        if let Some((paths, _)) = self.data.take() {
+            let paths: Vec<Path> = paths.into_iter().map(Path::cleanup).collect();
+            let snapped = snap_rounding::snap_round(&paths, i64::from(tolerance))?;
+            self.data = Snipper::normalize(snapped)?.data;
+        }
+        Ok(self)
+    }
+    // Pinches are split before the cleanup, which could otherwise remove
+    // the point where a path touches its own edge
+    fn prepare(
+        &self,
+        paths: Vec<Path>,
+        comparator: Box<dyn Comparator + Send>
+    ) -> Result<(Vec<Path>, Box<dyn Comparator + Send>), Error> {
+        let count = paths.len();
+        let paths: Vec<Path> = if self.split_pinches {
+            paths.into_iter().flat_map(Path::split_pinches).collect()
+        } else {
             paths
+        };
+        let mut changed = paths.len() != count;
+        let mut paths: Vec<Path> = if self.cleanup {
+            paths.into_iter().map(Path::cleanup).collect()
         } else {
-            Vec::new()
+            paths
+        };
+        // Nesting told by the sweep can be wrong for rings touching each other
+        // more than once, the strict output tells it by geometry instead
+        if self.strict {
+            paths.retain(|path| path.points().len() > 2 && path.area() > 0f64);
+            changed = true;
         }
+        // Split or dropped paths no longer match the indices of the sweep
+        let mut comparator: Box<dyn Comparator + Send> = if changed {
+            Box::new(GeometricComparator{})
+        } else {
+            comparator
+        };
+        // Slivers are told by the faces of the polygon, which is built anew without them
+        if self.min_area.is_some() || self.min_width.is_some() || self.max_thinness.is_some() {
+            let polygon = Polygon::build(paths.clone(), &mut *comparator)?;
+            if let Some(polygon) = polygon.remove_slivers(|face| self.is_sliver(face))? {
+                paths = polygon.into_paths();
+                comparator = Box::new(GeometricComparator{});
+            }
+        }
+        Ok((paths, comparator))
     }
-    pub fn polygon(self) -> Result<Polygon, Error> {
-        if let Some((paths, mut comparator)) = self.data {
-            Polygon::build(paths, &mut comparator)
+    // Canonical and strict output and the sliver filters need the polygon,
+    // which may fail to build or, for strict output, not be valid
+    pub fn paths(mut self) -> Result<Vec<Path>, Error> {
+        if self.canonical || self.strict {
+            return Ok(self.polygon()?.into_paths());
+        }
+        match self.data.take() {
+            Some((paths, comparator)) => Ok(self.prepare(paths, comparator)?.0),
+            None => Ok(Vec::new())
+        }
+    }
+    pub fn polygon(mut self) -> Result<Polygon, Error> {
+        if let Some((paths, comparator)) = self.data.take() {
+            let (paths, mut comparator) = self.prepare(paths, comparator)?;
+            self.finish(Polygon::build(paths, &mut *comparator)?)
         } else {
             unsafe { Polygon::flat(vec![]) }
         }
     }
+    fn finish(&self, mut polygon: Polygon) -> Result<Polygon, Error> {
+        if self.strict {
+            polygon = polygon.untangle()?;
+            if let Some(point) = polygon.first_invalid_point() {
+                return Err(Error::NotSimpleError { point });
+            }
+        }
+        if self.canonical {
+            Ok(polygon.canonical())
+        } else {
+            Ok(polygon)
+        }
+    }
+}
+
+// Takes the output paths one by one as the sweep completes them, so they
+// needn't all be held in memory. Failures of the sink are best reported
+// as sink errors, they abort the operation
+pub trait PathSink {
+    fn push_path(&mut self, path: Path) -> Result<(), Error>;
+}
+impl PathSink for Vec<Path> {
+    fn push_path(&mut self, path: Path) -> Result<(), Error> {
+        self.push(path);
+        Ok(())
+    }
 }
 
 pub struct Snipper {}
@@ -46,20 +227,199 @@ impl Snipper {
         clipping: T,
         operation: &'static Operation
     ) -> Result<Solution, Error> {
-        let mut queue = Queue::build(subject, clipping)?;
+        // Operands apart from each other don't interact, each only needs normalizing
+        // on its own and what the operation keeps of them is concatenated
+        let apart = match (subject.bounds(), clipping.bounds()) {
+            (Some(subject_bounds), Some(clipping_bounds)) => !Bounds::have_contact(subject_bounds, clipping_bounds),
+            _ => false
+        };
+        if apart {
+            let mut solution = Solution::new(None);
+            for (operand, paths) in [(Operand::Subject, subject.paths()), (Operand::Clipping, clipping.paths())] {
+                if (operation.test)(operand, primitives::Position::Out) {
+                    solution = Solution::joined(solution, Snipper::alone(&paths)?);
+                }
+            }
+            return Ok(solution);
+        }
+        let (queue, passed) = Snipper::queue(&subject, &clipping, operation)?;
+        Ok(Snipper::solution(Snipper::sweep(queue, operation)?, passed))
+    }
+    fn solution(swept: Option<(Vec<Path>, PathComparator)>, mut passed: Vec<Path>) -> Solution {
+        if passed.is_empty() {
+            Solution::new(swept.map(|(paths, comparator)| {
+                (paths, Box::new(comparator) as Box<dyn Comparator + Send>)
+            }))
+        } else {
+            let (mut paths, comparator) = match swept {
+                Some((paths, comparator)) => (paths, Some(comparator)),
+                None => (Vec::new(), None)
+            };
+            let swept = paths.len();
+            paths.append(&mut passed);
+            Solution::new(Some((paths, Box::new(MixedComparator { comparator, swept }))))
+        }
+    }
+    // Paths of one operand normalized by themselves
+    fn alone(paths: &[&Path]) -> Result<Solution, Error> {
+        let (swept, passed) = Snipper::partition(paths, &[], Operand::Subject, &Operation::XOR);
+        let mut queue = Queue::new();
+        queue.add_paths(&swept, Operand::Subject)?;
+        let passed = passed.into_iter().cloned().collect();
+        Ok(Snipper::solution(Snipper::sweep(queue, &Operation::XOR)?, passed))
+    }
+    // Paths are written into the sink cleaned up, in no particular order,
+    // as soon as the sweep line passes their rightmost point. The nesting
+    // of the paths is not told, it is up to whoever reads them
+    pub fn perform_operation_into<T: Shape, S: PathSink>(
+        subject: T,
+        clipping: T,
+        operation: &'static Operation,
+        sink: &mut S
+    ) -> Result<(), Error> {
+        let (queue, passed) = Snipper::queue(&subject, &clipping, operation)?;
+        let drain = |paths: Vec<Path>, sink: &mut S| {
+            for path in paths {
+                sink.push_path(path.cleanup())?;
+            }
+            Ok(())
+        };
+        drain(passed, sink)?;
+        if let Some(mut pic) = Snipper::draw(queue, operation, |state| drain(state.take_paths()?, sink))? {
+            drain(pic.take_paths()?, sink)?;
+        }
+        Ok(())
+    }
+    // Queue of the paths entering the sweep, along with those passed right away
+    fn queue<T: Shape>(subject: &T, clipping: &T, operation: &'static Operation) -> Result<(Queue, Vec<Path>), Error> {
+        let (subject_paths, clipping_paths) = (subject.paths(), clipping.paths());
+        let (subject_swept, subject_passed) = Snipper::partition(
+            &subject_paths, &clipping_paths, Operand::Subject, operation
+        );
+        let (clipping_swept, clipping_passed) = Snipper::partition(
+            &clipping_paths, &subject_paths, Operand::Clipping, operation
+        );
+        let mut queue = Queue::new();
+        queue.add_paths(&subject_swept, Operand::Subject)?;
+        queue.add_paths(&clipping_swept, Operand::Clipping)?;
+        let passed: Vec<Path> = subject_passed.into_iter().chain(clipping_passed).cloned().collect();
+        Ok((queue, passed))
+    }
+    // For operands streamed into the queue ring by ring. None of the paths
+    // can skip the sweep, the queue holds edges only
+    pub fn perform_queued(queue: Queue, operation: &'static Operation) -> Result<Solution, Error> {
+        let swept = Snipper::sweep(queue, operation)?;
+        Ok(Solution::new(swept.map(|(paths, comparator)| {
+            (paths, Box::new(comparator) as Box<dyn Comparator + Send>)
+        })))
+    }
+    // A path whose bounds collide with no path of the other operand lies outside
+    // of it. Unless the operation keeps such parts, it is dropped right away. If it
+    // can't interact with anything, being strictly convex and not even touching
+    // other paths, it is passed to the output without entering the sweep.
+    fn partition<'a>(
+        paths: &[&'a Path],
+        others: &[&Path],
+        operand: Operand,
+        operation: &Operation
+    ) -> (Vec<&'a Path>, Vec<&'a Path>) {
+        let collide = Snipper::meet_any(paths, others, Bounds::have_collision);
+        let touch = Snipper::meet_any(paths, others, Bounds::have_contact);
+        let touch_own = Snipper::meet_any(paths, paths, Bounds::have_contact);
+        let mut swept = Vec::new();
+        let mut passed = Vec::new();
+        for (index, path) in paths.iter().enumerate() {
+            if collide[index] {
+                swept.push(*path);
+            } else if !(operation.test)(operand, primitives::Position::Out) {
+                continue;
+            } else if path.is_strictly_convex() && !touch[index] && !touch_own[index] {
+                passed.push(*path);
+            } else {
+                swept.push(*path);
+            }
+        }
+        (swept, passed)
+    }
+    // For each path, whether its bounds pass the test with those of any other path.
+    // Both are taken by the left of their bounds, others ending left of the path
+    // can't meet the paths still to come and are let go
+    fn meet_any(paths: &[&Path], others: &[&Path], test: fn(&Bounds, &Bounds) -> bool) -> Vec<bool> {
+        fn by_left<'a>(paths: &[&'a Path]) -> Vec<(usize, &'a Bounds)> {
+            let mut sorted: Vec<(usize, &Bounds)> = paths.iter().enumerate()
+                .filter_map(|(index, path)| path.bounds().map(|bounds| (index, bounds)))
+                .collect();
+            sorted.sort_by_key(|(_, bounds)| bounds.left());
+            sorted
+        }
+        let (sorted, sorted_others) = (by_left(paths), by_left(others));
+        let mut met = vec![false; paths.len()];
+        let mut active: Vec<(usize, &Bounds)> = Vec::new();
+        let mut next = 0;
+        for (index, bounds) in sorted {
+            while next < sorted_others.len() && sorted_others[next].1.left() <= bounds.right() {
+                active.push(sorted_others[next]);
+                next += 1;
+            }
+            active.retain(|(_, other)| other.right() >= bounds.left());
+            met[index] = active.iter().any(|(other_index, other)| {
+                !std::ptr::eq(paths[index], others[*other_index]) && test(bounds, other)
+            });
+        }
+        met
+    }
+    fn sweep(queue: Queue, operation: &'static Operation) -> Result<Option<(Vec<Path>, PathComparator)>, Error> {
+        match Snipper::draw(queue, operation, |_| Ok(()))? {
+            Some(pic) => {
+                let (paths, routes) = pic.build_paths()?;
+                let comparator = PathComparator {
+                    routes
+                };
+                Ok(Some((paths, comparator)))
+            },
+            None => Ok(None)
+        }
+    }
+    // Each time the sweep line moves on, the chains left behind are done
+    // and the state is handed over to take the paths they close
+    fn draw<F: FnMut(&mut DrawingAlgorithm) -> Result<(), Error>>(
+        queue: Queue,
+        operation: &'static Operation,
+        mut moved: F
+    ) -> Result<Option<PathsInConstruction>, Error> {
+        let drawings = Snipper::draw_each(queue, 1, |ray, partitions| {
+            let (edge, partitions) = ray.yield_edge(partitions, operation);
+            (partitions, if edge.is_some() { 1 } else { 0 })
+        }, |states| moved(&mut states[0]))?;
+        Ok(drawings.and_then(|drawings| drawings.into_iter().next()))
+    }
+    // Several drawings made by one sweep, the edge of each ray
+    // going to those set in the mask the selector gives
+    fn draw_each<S, F>(
+        mut queue: Queue,
+        count: usize,
+        mut select: S,
+        mut moved: F
+    ) -> Result<Option<Vec<PathsInConstruction>>, Error>
+    where
+        S: for<'a> FnMut(&'a Ray, (&'a Partition, &'a Partition)) -> ((&'a Partition, &'a Partition), usize),
+        F: FnMut(&mut [DrawingAlgorithm]) -> Result<(), Error>
+    {
         let mut next: Option<Coordinate> = queue.next_x();
         let mut left: BTreeMap<Key, Ray> = BTreeMap::new();
         let mut positions: BTreeMap<Float, Position> = BTreeMap::new();
         if let Some(x) = next {
-            let mut state = DrawingAlgorithm::initial_state(x);
+            let mut states: Vec<DrawingAlgorithm> = (0..count).map(|_| DrawingAlgorithm::initial_state(x)).collect();
             while let Some(x) = next {
                 let scope = Scope::build(left, positions, &x, &mut queue)?;
                 let mut partitions = (&Partition::OUT_OUT, &Partition::OUT_OUT);
                 for ray in scope.iter() {
-                    let tuple = ray.yield_edge(partitions, operation);
-                    partitions = tuple.1;
-                    if let Some(edge) = tuple.0 {
-                        state.draw_edge(edge);
+                    let (selected, mask) = select(ray, partitions);
+                    partitions = selected;
+                    for (index, state) in states.iter_mut().enumerate() {
+                        if mask & (1 << index) != 0 {
+                            state.draw_edge(ray.edge());
+                        }
                     }
                 }
                 let (new_left, new_positions, next_scope) = scope.pass_over();
@@ -73,18 +433,13 @@ impl Snipper {
                     (None, None) => None,
                 };
                 if let Some(next_x) = next {
-                    state = state.next_state(next_x);
+                    states = states.into_iter().map(|state| state.next_state(next_x)).collect::<Result<Vec<_>, Error>>()?;
+                    moved(&mut states)?;
                 }
             }
-            let pic = state.terminate_all();
-            let (paths, routes) = pic.build_paths();
-            let comparator = PathComparator {
-                routes
-            };
-            Ok(Solution::new(Some((paths, comparator))))
-
+            Ok(Some(states.into_iter().map(DrawingAlgorithm::terminate_all).collect::<Result<Vec<_>, Error>>()?))
         } else {
-            Ok(Solution::new(None))
+            Ok(None)
         }
     }
 
@@ -105,47 +460,426 @@ impl Snipper {
         let null = unsafe { Polygon::flat(vec![])? };
         Snipper::perform_operation(non_normal, null, &Operation::XOR)
     }
+    pub fn clip_to_bounds(polygon: &Polygon, bounds: &Bounds) -> Result<Solution, Error> {
+        let paths = rectangle_clipping::clip(polygon, bounds)?;
+        if paths.is_empty() {
+            Ok(Solution::new(None))
+        } else {
+            Ok(Solution::new(Some((paths, Box::new(GeometricComparator{})))))
+        }
+    }
+    // Tiles are clipped independently, in parallel with the rayon feature
+    pub fn clip_to_grid(polygon: &Polygon, tile_bounds: &[Bounds]) -> Vec<Result<Solution, Error>> {
+        let clip = |tile: &Bounds| Snipper::clip_to_bounds(polygon, tile);
+        #[cfg(feature = "rayon")]
+        let solutions = tile_bounds.par_iter().map(clip).collect();
+        #[cfg(not(feature = "rayon"))]
+        let solutions = tile_bounds.iter().map(clip).collect();
+        solutions
+    }
+    pub fn batch(
+        operands: Vec<(Polygon, Polygon)>,
+        operation: fn(Polygon, Polygon) -> Result<Solution, Error>
+    ) -> Vec<Result<Solution, Error>> {
+        let perform = |(subject, clipping): (Polygon, Polygon)| operation(subject, clipping);
+        #[cfg(feature = "rayon")]
+        let solutions = operands.into_par_iter().map(perform).collect();
+        #[cfg(not(feature = "rayon"))]
+        let solutions = operands.into_iter().map(perform).collect();
+        solutions
+    }
+    // Faces of the planar partition made by both operands, each part
+    // once, labelled with the operands it belongs to. One sweep draws
+    // them all, an edge goes to the faces of the operands on either of
+    // its sides. Points where any face turns are kept by all of them,
+    // so the faces fit each other exactly
+    pub fn overlay(subject: Polygon, clipping: Polygon) -> Result<Vec<(Polygon, Membership)>, Error> {
+        const MEMBERSHIPS: [Membership; 3] = [Membership::Subject, Membership::Clipping, Membership::Both];
+        let label = |subject: primitives::Position, clipping: primitives::Position| {
+            match (subject, clipping) {
+                (primitives::Position::In, primitives::Position::In) => 4,
+                (primitives::Position::In, _) => 1,
+                (_, primitives::Position::In) => 2,
+                _ => 0
+            }
+        };
+        let mut queue = Queue::new();
+        queue.add_paths(&Shape::paths(&subject), Operand::Subject)?;
+        queue.add_paths(&Shape::paths(&clipping), Operand::Clipping)?;
+        let drawings = Snipper::draw_each(queue, MEMBERSHIPS.len(), |ray, partitions| {
+            let partitions = ray.yield_edge(partitions, &Operation::XOR).1;
+            let counterclockwise = label(partitions.0.counterclockwise, partitions.1.counterclockwise);
+            let clockwise = label(partitions.0.clockwise, partitions.1.clockwise);
+            (partitions, counterclockwise ^ clockwise)
+        }, |_| Ok(()))?;
+        let mut drawn: Vec<(Vec<Path>, PathComparator)> = Vec::new();
+        for pic in drawings.unwrap_or_default() {
+            let (paths, routes) = pic.build_paths()?;
+            drawn.push((paths, PathComparator { routes }));
+        }
+        let corners: HashSet<Point> = drawn.iter()
+            .flat_map(|(paths, _)| paths)
+            .flat_map(Path::corners)
+            .cloned()
+            .collect();
+        let mut faces: Vec<(Polygon, Membership)> = Vec::new();
+        for ((paths, mut comparator), membership) in drawn.into_iter().zip(MEMBERSHIPS) {
+            let paths = paths.into_iter().map(|path| path.cleanup_except(|point| corners.contains(point))).collect();
+            let polygon = Polygon::build(paths, &mut comparator)?;
+            faces.extend(polygon.components().into_iter().map(|component| (component, membership)));
+        }
+        Ok(faces)
+    }
+    // DE-9IM matrix of the subject and the clipping shape,
+    // told by a single sweep that draws nothing
+    pub fn relate<A: Shape, B: Shape>(subject: &A, clipping: &B) -> Result<IntersectionMatrix, Error> {
+        let survey = Snipper::survey(subject, clipping, |_| false)?;
+        Ok(survey.matrix())
+    }
+    // The predicates stop the sweep as soon as the answer is known
+    pub fn intersects<A: Shape, B: Shape>(subject: &A, clipping: &B) -> Result<bool, Error> {
+        let survey = Snipper::survey(subject, clipping, |survey| survey.interiors || survey.boundaries)?;
+        Ok(survey.interiors || survey.boundaries)
+    }
+    pub fn disjoint<A: Shape, B: Shape>(subject: &A, clipping: &B) -> Result<bool, Error> {
+        Ok(!Snipper::intersects(subject, clipping)?)
+    }
+    // No part of the clipping shape lies outside of the subject
+    pub fn contains<A: Shape, B: Shape>(subject: &A, clipping: &B) -> Result<bool, Error> {
+        let survey = Snipper::survey(subject, clipping, |survey| survey.clipping_only)?;
+        Ok(survey.interiors && !survey.clipping_only)
+    }
+    pub fn within<A: Shape, B: Shape>(subject: &A, clipping: &B) -> Result<bool, Error> {
+        Snipper::contains(clipping, subject)
+    }
+    // Boundaries meet, interiors don't
+    pub fn touches<A: Shape, B: Shape>(subject: &A, clipping: &B) -> Result<bool, Error> {
+        let survey = Snipper::survey(subject, clipping, |survey| survey.interiors)?;
+        Ok(!survey.interiors && survey.boundaries)
+    }
+    // Interiors meet, neither shape contains the other
+    pub fn overlaps<A: Shape, B: Shape>(subject: &A, clipping: &B) -> Result<bool, Error> {
+        let overlapping = |survey: &Survey| survey.interiors && survey.subject_only && survey.clipping_only;
+        let survey = Snipper::survey(subject, clipping, overlapping)?;
+        Ok(overlapping(&survey))
+    }
+    // Points where the boundaries of the operands meet, with the input
+    // segments meeting there, found by the same sweep the operations use
+    pub fn intersection_points<A: Shape, B: Shape>(subject: &A, clipping: &B) -> Result<Vec<IntersectionPoint>, Error> {
+        let mut queue = Queue::new();
+        queue.track_lineage();
+        queue.add_paths(&subject.paths(), Operand::Subject)?;
+        queue.add_paths(&clipping.paths(), Operand::Clipping)?;
+        IntersectionAlgorithm::sweep(&mut queue)?;
+        let lineage = queue.take_lineage().expect("Lineage tracked");
+        Ok(lineage.intersection_points())
+    }
+    // Points where the paths cross or touch themselves or each other,
+    // including both ends of every stretch where segments overlap
+    pub fn self_intersections(paths: Vec<Path>) -> Result<Vec<IntersectionPoint>, Error> {
+        let mut queue = Queue::new();
+        queue.track_lineage();
+        queue.add_paths(&paths.iter().collect::<Vec<&Path>>(), Operand::Subject)?;
+        IntersectionAlgorithm::sweep(&mut queue)?;
+        let lineage = queue.take_lineage().expect("Lineage tracked");
+        Ok(lineage.self_intersections())
+    }
+    // Walks the rays the way an operation does, noting whether the faces
+    // on both sides of each ray lie in either shape and whether boundaries
+    // of both shapes meet, edges being snipped wherever they do
+    fn survey<A: Shape, B: Shape>(subject: &A, clipping: &B, done: fn(&Survey) -> bool) -> Result<Survey, Error> {
+        let mut survey = Survey::default();
+        match (subject.bounds(), clipping.bounds()) {
+            (Some(a), Some(b)) if Bounds::have_contact(a, b) => {},
+            (a, b) => {
+                survey.subject_only = a.is_some();
+                survey.clipping_only = b.is_some();
+                survey.subject_boundary_outside = a.is_some();
+                survey.clipping_boundary_outside = b.is_some();
+                return Ok(survey);
+            }
+        }
+        let mut queue = Queue::new();
+        queue.add_paths(&subject.paths(), Operand::Subject)?;
+        queue.add_paths(&clipping.paths(), Operand::Clipping)?;
+        let mut next: Option<Coordinate> = queue.next_x();
+        let mut left: BTreeMap<Key, Ray> = BTreeMap::new();
+        let mut positions: BTreeMap<Float, Position> = BTreeMap::new();
+        // Each edge is met once, in the scope where it ends
+        let mut subject_points: HashSet<Point> = HashSet::new();
+        let mut clipping_points: HashSet<Point> = HashSet::new();
+        while let Some(x) = next {
+            let scope = Scope::build(left, positions, &x, &mut queue)?;
+            let mut partitions = (&Partition::OUT_OUT, &Partition::OUT_OUT);
+            for ray in scope.iter() {
+                partitions = ray.yield_edge(partitions, &Operation::XOR).1;
+                survey.note_face(partitions.0.counterclockwise, partitions.1.counterclockwise);
+                survey.note_face(partitions.0.clockwise, partitions.1.clockwise);
+                let edge = ray.edge();
+                survey.note_edge(edge, partitions);
+                for point in [edge.upper_left(), edge.lower_right()] {
+                    if edge.count(Some(Operand::Subject)) % 2 == 1 {
+                        survey.boundaries |= clipping_points.contains(point);
+                        subject_points.insert(point.clone());
+                    }
+                    if edge.count(Some(Operand::Clipping)) % 2 == 1 {
+                        survey.boundaries |= subject_points.contains(point);
+                        clipping_points.insert(point.clone());
+                    }
+                }
+            }
+            if done(&survey) { return Ok(survey); }
+            let (new_left, new_positions, next_scope) = scope.pass_over();
+            left = new_left;
+            positions = new_positions;
+            next = match (next_scope, queue.next_x()) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (Some(a), None) => Some(a),
+                (None, Some(b)) => Some(b),
+                (None, None) => None,
+            };
+        }
+        Ok(survey)
+    }
+}
+// What a sweep over two shapes found out about the areas
+// and the boundaries they share
+#[derive(Default)]
+struct Survey {
+    interiors: bool,
+    subject_only: bool,
+    clipping_only: bool,
+    boundaries: bool,
+    shared_edges: bool,
+    subject_boundary_inside: bool,
+    subject_boundary_outside: bool,
+    clipping_boundary_inside: bool,
+    clipping_boundary_outside: bool
+}
+impl Survey {
+    fn note_face(&mut self, subject: primitives::Position, clipping: primitives::Position) {
+        match (subject, clipping) {
+            (primitives::Position::In, primitives::Position::In) => self.interiors = true,
+            (primitives::Position::In, _) => self.subject_only = true,
+            (_, primitives::Position::In) => self.clipping_only = true,
+            _ => {}
+        }
+    }
+    // An edge of one boundary only has the same position
+    // in the other shape on both of its sides
+    fn note_edge(&mut self, edge: &Edge, partitions: (&Partition, &Partition)) {
+        let subject = edge.count(Some(Operand::Subject)) % 2 == 1;
+        let clipping = edge.count(Some(Operand::Clipping)) % 2 == 1;
+        let inside = |partition: &Partition| partition.clockwise == primitives::Position::In;
+        match (subject, clipping) {
+            (true, true) => self.shared_edges = true,
+            (true, false) if inside(partitions.1) => self.subject_boundary_inside = true,
+            (true, false) => self.subject_boundary_outside = true,
+            (false, true) if inside(partitions.0) => self.clipping_boundary_inside = true,
+            (false, true) => self.clipping_boundary_outside = true,
+            (false, false) => {}
+        }
+    }
+    fn matrix(&self) -> IntersectionMatrix {
+        let dimension = |present: bool, dimension: Dimension| if present { dimension } else { Dimension::Empty };
+        let boundaries = if self.shared_edges {
+            Dimension::One
+        } else {
+            dimension(self.boundaries, Dimension::Zero)
+        };
+        IntersectionMatrix::new([
+            [
+                dimension(self.interiors, Dimension::Two),
+                dimension(self.clipping_boundary_inside, Dimension::One),
+                dimension(self.subject_only, Dimension::Two)
+            ],
+            [
+                dimension(self.subject_boundary_inside, Dimension::One),
+                boundaries,
+                dimension(self.subject_boundary_outside, Dimension::One)
+            ],
+            [
+                dimension(self.clipping_only, Dimension::Two),
+                dimension(self.clipping_boundary_outside, Dimension::One),
+                Dimension::Two
+            ]
+        ])
+    }
 }
 pub struct PathComparator {
     routes: crate::drawing_algorithm::routes::Routes
 }
 impl PathComparator {
-    pub fn count_chains_above(&mut self, a: usize, b: usize) -> usize {
+    pub fn count_chains_above(&mut self, a: usize, b: usize) -> Option<usize> {
         let mut first_index = self.routes.first(&a);
-        let before_first = self.routes.structure().get(first_index.expect("Expected to be there"));
-        debug_assert!(before_first.is_some(), "Index expected to be there");
+        let before_first = self.routes.structure().get(first_index.index()?);
         let mut count = 0;
-        let mut option = before_first;
+        let mut option = Some(before_first?);
         while let Some(previous) = option {
             if let BuilderIndex::Some(index) = previous {
-                let chain_index = previous.unwrap();
-                if self.routes.belongs_to_path(&chain_index, &b) {
+                if self.routes.belongs_to_path(index, &b) {
                     count += 1;
-                } else if let FirstIndex::Random(index) = first_index {
-                    if self.routes.belongs_to_path(&chain_index, &a) {
-                        first_index = FirstIndex::Random(index);
+                } else if let FirstIndex::Random(first) = first_index {
+                    if self.routes.belongs_to_path(index, &a) {
+                        first_index = FirstIndex::Random(first);
                     }
                 }
-                option = Some(self.routes.structure().get(*index).expect("Previous index expected to be there"));
+                option = Some(self.routes.structure().get(*index)?);
             } else {
                 option = None;
             }
         }
-        self.routes.confirm_first(first_index.expect("Expected to be there"), &a);
-        count
+        self.routes.confirm_first(first_index.index()?, &a);
+        Some(count)
     }
 }
 impl Comparator for PathComparator {
     fn compare(&mut self, a: &Path, b: &Path, a_index: usize, b_index: usize) -> Relation {
-        if Bounds::have_collision(a.bounds().unwrap(), b.bounds().unwrap()) {
-            let a_cnt = self.count_chains_above(a_index, b_index) % 2;
-            let b_cnt = self.count_chains_above(b_index, a_index) % 2;
-            debug_assert!(!(a_cnt != 0 && b_cnt != 0), "Both paths can't have odd number of neighbors");
-            if a_cnt == 1 { return Relation::Contained; }
-            if b_cnt == 1 { return Relation::Contains; }
-            Relation::Unrelated
+        let collision = match (a.bounds(), b.bounds()) {
+            (Some(a_bounds), Some(b_bounds)) => Bounds::have_collision(a_bounds, b_bounds),
+            _ => false
+        };
+        if collision {
+            let a_cnt = self.count_chains_above(a_index, b_index);
+            let b_cnt = self.count_chains_above(b_index, a_index);
+            match (a_cnt, b_cnt) {
+                (Some(a_cnt), Some(b_cnt)) => {
+                    match (a_cnt % 2, b_cnt % 2) {
+                        (1, 0) => Relation::Contained,
+                        (0, 1) => Relation::Contains,
+                        (0, 0) => Relation::Unrelated,
+                        _ => Relation::Unknown
+                    }
+                },
+                _ => Relation::Unknown
+            }
         } else {
             Relation::Unrelated
         }
     }
 }
+// Relations between swept paths are known from the sweep,
+// paths passed around it are compared geometrically
+struct MixedComparator {
+    comparator: Option<PathComparator>,
+    swept: usize
+}
+// Relations among paths of one operand passed on, paths of different
+// operands apart from each other are unrelated
+struct JoinedComparator {
+    first: Box<dyn Comparator + Send>,
+    second: Box<dyn Comparator + Send>,
+    split: usize
+}
+impl Comparator for JoinedComparator {
+    fn compare(&mut self, a: &Path, b: &Path, a_index: usize, b_index: usize) -> Relation {
+        match (a_index < self.split, b_index < self.split) {
+            (true, true) => self.first.compare(a, b, a_index, b_index),
+            (false, false) => self.second.compare(a, b, a_index - self.split, b_index - self.split),
+            _ => Relation::Unrelated
+        }
+    }
+}
+impl Comparator for MixedComparator {
+    fn compare(&mut self, a: &Path, b: &Path, a_index: usize, b_index: usize) -> Relation {
+        match &mut self.comparator {
+            Some(comparator) if a_index < self.swept && b_index < self.swept => {
+                comparator.compare(a, b, a_index, b_index)
+            },
+            _ => GeometricComparator{}.compare(a, b, a_index, b_index)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::{Solution, Snipper};
+    use crate::shape::{Path, Polygon};
+    use crate::shape::polygon::{Comparator, Relation};
+    use crate::primitives::Point;
+    use crate::Transform;
+    use crate::error::Error;
+
+    // Tells nothing about the nesting, so no polygon can be built
+    struct Undecided {}
+    impl Comparator for Undecided {
+        fn compare(&mut self, _: &Path, _: &Path, _: usize, _: usize) -> Relation {
+            Relation::Unknown
+        }
+    }
+    fn path(points: &[(i32, i32)]) -> Path {
+        Path::new(&points.iter().map(|&(x, y)| Point::new(x, y).unwrap()).collect())
+    }
+    fn undecided(paths: Vec<Path>) -> Solution {
+        Solution::new(Some((paths, Box::new(Undecided{}))))
+    }
+
+    #[test]
+    fn canonical_failure_test() {
+        let squares = vec![
+            path(&[(0, 0), (4, 0), (4, 4), (0, 4)]),
+            path(&[(8, 0), (12, 0), (12, 4), (8, 4)])
+        ];
+        assert_eq!(undecided(squares.clone()).paths().unwrap().len(), 2);
+        match undecided(squares).canonical().paths() {
+            Err(Error::FatalError(_)) => (),
+            other => panic!("Expected FatalError, got {:?}", other.map(|paths| paths.len()))
+        }
+    }
+    #[test]
+    fn strict_failure_test() {
+        // Touching itself, as the sweep never draws it
+        let pinched = path(&[(0, 0), (4, 4), (8, 0), (8, 8), (4, 4), (0, 8)]);
+        let solution = undecided(vec![]).strict();
+        match solution.finish(unsafe { Polygon::trivial(pinched) }) {
+            Err(Error::NotSimpleError { point }) => assert_eq!(point, Point::new(4, 4).unwrap()),
+            other => panic!("Expected NotSimpleError, got {:?}", other.map(|polygon| polygon.paths().len()))
+        }
+        let squares = vec![
+            path(&[(0, 0), (4, 0), (4, 4), (0, 4)]),
+            path(&[(8, 0), (12, 0), (12, 4), (8, 4)])
+        ];
+        assert_eq!(undecided(squares).strict().paths().unwrap().len(), 2);
+    }
+    #[test]
+    fn sliver_failure_test() {
+        let squares = vec![
+            path(&[(0, 0), (4, 0), (4, 4), (0, 4)]),
+            path(&[(8, 0), (12, 0), (12, 4), (8, 4)])
+        ];
+        match undecided(squares).min_area(1.0).paths() {
+            Err(Error::FatalError(_)) => (),
+            other => panic!("Expected FatalError, got {:?}", other.map(|paths| paths.len()))
+        }
+    }
+    // Parent of each path by the areas of both
+    fn parents(polygon: &Polygon) -> Vec<(f64, Option<f64>)> {
+        let paths = polygon.paths();
+        let mut parents: Vec<(f64, Option<f64>)> = polygon.structure().iter().zip(paths).map(|(record, path)| {
+            (path.area().abs(), record.parent().map(|parent| paths[parent].area().abs()))
+        }).collect();
+        parents.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        parents
+    }
+    #[test]
+    fn split_pinches_nesting_test() {
+        // Squares touching at a corner, the upper one wider and with a hole
+        let outer = path(&[(0, 0), (4, 0), (4, 4), (10, 4), (10, 10), (4, 10), (4, 4), (0, 4)]);
+        let hole = path(&[(5, 5), (5, 7), (7, 7), (7, 5)]);
+        let split = Snipper::normalize(vec![outer.clone(), hole.clone()]).unwrap().split_pinches().polygon().unwrap();
+        let expected = vec![(4f64, Some(36f64)), (16f64, None), (36f64, None)];
+        assert_eq!(parents(&split), expected);
+        let mirrored = Snipper::normalize(vec![outer, hole]).unwrap()
+            .transform(&Transform::identity().mirror_x()).unwrap()
+            .split_pinches()
+            .polygon().unwrap();
+        assert_eq!(parents(&mirrored), expected);
+        // A loop touching the loop of another ring only at a point
+        let crossed = path(&[(5, 11), (0, 10), (17, 7), (12, 12), (16, 2)]);
+        let whole = Snipper::normalize(vec![crossed.clone()]).unwrap().polygon().unwrap();
+        let split = Snipper::normalize(vec![crossed]).unwrap().split_pinches().polygon().unwrap();
+        assert_eq!(whole.area(), 28.5);
+        assert_eq!(split.area(), 28.5);
+    }
+}
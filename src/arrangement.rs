@@ -0,0 +1,214 @@
+use crate::primitives::{Point, Position};
+use crate::edge::Edge;
+use crate::shape::{Path, PathDirection};
+use crate::shape::path::{angular, coordinates};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug)]
+pub struct HalfEdge {
+    origin: usize,
+    twin: usize,
+    next: usize,
+    previous: usize,
+    face: usize,
+    subject: usize,
+    clipping: usize
+}
+impl HalfEdge {
+    pub fn origin(&self) -> usize { self.origin }
+    pub fn twin(&self) -> usize { self.twin }
+    pub fn next(&self) -> usize { self.next }
+    pub fn previous(&self) -> usize { self.previous }
+    // The face lies to the left of the half-edge with the y axis pointing up
+    pub fn face(&self) -> usize { self.face }
+    // Number of subject and clipping edges lying along the half-edge
+    pub fn subject(&self) -> usize { self.subject }
+    pub fn clipping(&self) -> usize { self.clipping }
+}
+#[derive(Clone, Debug)]
+pub struct Face {
+    outer: Option<usize>,
+    inner: Vec<usize>
+}
+impl Face {
+    // A half-edge of the outer boundary, none for the unbounded face
+    pub fn outer(&self) -> Option<usize> { self.outer }
+    // A half-edge of each boundary of a separate component lying within the face
+    pub fn inner(&self) -> &Vec<usize> { &self.inner }
+}
+// Planar graph of the split edges. Half-edges come in twin pairs,
+// the two halves of the edge with index i being 2i and 2i + 1.
+// The unbounded face has index zero.
+pub struct Arrangement {
+    vertices: Vec<Point>,
+    outgoing: Vec<Vec<usize>>,
+    half_edges: Vec<HalfEdge>,
+    faces: Vec<Face>
+}
+impl Arrangement {
+    pub(crate) fn build(edges: &[Edge]) -> Arrangement {
+        let mut vertices: Vec<Point> = edges.iter()
+            .flat_map(|edge| vec![edge.straight.start.clone(), edge.straight.end.clone()])
+            .collect();
+        vertices.sort_by_key(coordinates);
+        vertices.dedup();
+        let indices: HashMap<&Point, usize> = vertices.iter().enumerate().map(|(index, point)| (point, index)).collect();
+        let mut half_edges: Vec<HalfEdge> = Vec::with_capacity(2 * edges.len());
+        for edge in edges {
+            let (start, end) = (indices[&edge.straight.start], indices[&edge.straight.end]);
+            for origin in [start, end] {
+                let index = half_edges.len();
+                half_edges.push(HalfEdge {
+                    origin,
+                    twin: index ^ 1,
+                    next: index,
+                    previous: index,
+                    face: 0,
+                    subject: edge.subject,
+                    clipping: edge.clipping
+                });
+            }
+        }
+        let direction = |half_edges: &Vec<HalfEdge>, index: usize| {
+            let (start, end) = (coordinates(&vertices[half_edges[index].origin]), coordinates(&vertices[half_edges[index ^ 1].origin]));
+            (end.0 - start.0, end.1 - start.1)
+        };
+        // Counterclockwise around each vertex
+        let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); vertices.len()];
+        for (index, half_edge) in half_edges.iter().enumerate() {
+            outgoing[half_edge.origin].push(index);
+        }
+        for around in outgoing.iter_mut() {
+            around.sort_by(|a, b| angular(direction(&half_edges, *a), direction(&half_edges, *b)));
+        }
+        // Turning as far right as possible keeps the face on the left
+        for index in 0..half_edges.len() {
+            let around = &outgoing[half_edges[index ^ 1].origin];
+            let position = around.iter().position(|other| *other == index ^ 1).unwrap();
+            let next = around[(position + around.len() - 1) % around.len()];
+            half_edges[index].next = next;
+            half_edges[next].previous = index;
+        }
+        let mut arrangement = Arrangement {
+            vertices,
+            outgoing,
+            half_edges,
+            faces: vec![Face { outer: None, inner: Vec::new() }]
+        };
+        arrangement.build_faces();
+        arrangement
+    }
+    // Boundaries running counterclockwise with the y axis pointing up, that is
+    // oriented as outer paths, enclose faces of their own, the others
+    // belong to the smallest face around them, or to the unbounded one
+    fn build_faces(&mut self) {
+        let mut visited = vec![false; self.half_edges.len()];
+        let mut inner: Vec<usize> = Vec::new();
+        let mut outer: Vec<(usize, Path)> = Vec::new();
+        for start in 0..self.half_edges.len() {
+            if visited[start] { continue; }
+            let cycle = self.cycle(start);
+            for index in cycle.iter() {
+                visited[*index] = true;
+            }
+            let path = self.path(start);
+            if path.direction() == PathDirection::Clockwise {
+                outer.push((start, path));
+            } else {
+                inner.push(start);
+            }
+        }
+        outer.sort_by(|a, b| a.1.area().partial_cmp(&b.1.area()).unwrap());
+        for (start, _) in outer.iter() {
+            let face = self.faces.len();
+            self.faces.push(Face { outer: Some(*start), inner: Vec::new() });
+            self.set_face(*start, face);
+        }
+        for start in inner {
+            let point = coordinates(&self.vertices[self.half_edges[start].origin]);
+            let face = outer.iter()
+                .position(|(_, path)| path.position_doubled(2 * point.0, 2 * point.1) == Position::In)
+                .map_or(0, |index| index + 1);
+            self.faces[face].inner.push(start);
+            self.set_face(start, face);
+        }
+    }
+    fn set_face(&mut self, start: usize, face: usize) {
+        for index in self.cycle(start) {
+            self.half_edges[index].face = face;
+        }
+    }
+    pub fn vertices(&self) -> &Vec<Point> {
+        &self.vertices
+    }
+    pub fn half_edges(&self) -> &Vec<HalfEdge> {
+        &self.half_edges
+    }
+    pub fn faces(&self) -> &Vec<Face> {
+        &self.faces
+    }
+    // Half-edges leaving the vertex, counterclockwise with the y axis pointing up
+    pub fn outgoing(&self, vertex: usize) -> &Vec<usize> {
+        &self.outgoing[vertex]
+    }
+    pub fn destination(&self, half_edge: usize) -> usize {
+        self.half_edges[self.half_edges[half_edge].twin].origin
+    }
+    // Half-edges of the boundary the given one belongs to, in order
+    pub fn cycle(&self, start: usize) -> Vec<usize> {
+        let mut cycle = vec![start];
+        let mut index = self.half_edges[start].next;
+        while index != start {
+            cycle.push(index);
+            index = self.half_edges[index].next;
+        }
+        cycle
+    }
+    pub fn path(&self, start: usize) -> Path {
+        let points: Vec<Point> = self.cycle(start).into_iter()
+            .map(|index| self.vertices[self.half_edges[index].origin].clone())
+            .collect();
+        Path::new(&points)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Arrangement;
+    use crate::edge::Edge;
+    use crate::operation::Operand;
+    use crate::primitives::Point;
+
+    fn edges(operand: Operand, points: Vec<(i32, i32)>, first: usize) -> Vec<Edge> {
+        let points: Vec<Point> = points.into_iter().map(|(x, y)| Point::new(x, y).unwrap()).collect();
+        (0..points.len())
+            .map(|index| Edge::original(first + index, operand, &points[index], &points[(index + 1) % points.len()]).unwrap())
+            .collect()
+    }
+    #[test]
+    fn arrangement_test() {
+        let mut all = edges(Operand::Subject, vec![(0, 0), (10, 0), (10, 10), (0, 10)], 0);
+        all.extend(edges(Operand::Clipping, vec![(2, 2), (4, 2), (4, 4), (2, 4)], 4));
+        all.extend(edges(Operand::Clipping, vec![(20, 0), (22, 0), (21, 2)], 8));
+        let arrangement = Arrangement::build(&all);
+        assert_eq!(arrangement.vertices().len(), 11);
+        assert_eq!(arrangement.half_edges().len(), 22);
+        assert_eq!(arrangement.faces().len(), 4);
+        let unbounded = &arrangement.faces()[0];
+        assert_eq!(unbounded.outer(), None);
+        assert_eq!(unbounded.inner().len(), 2);
+        let areas: Vec<f64> = arrangement.faces()[1..].iter().map(|face| arrangement.path(face.outer().unwrap()).area()).collect();
+        assert_eq!(areas, vec![2.0, 4.0, 100.0]);
+        let square = &arrangement.faces()[3];
+        assert_eq!(square.inner().len(), 1);
+        assert_eq!(arrangement.path(square.inner()[0]).area(), 4.0);
+        for (index, half_edge) in arrangement.half_edges().iter().enumerate() {
+            assert_eq!(arrangement.half_edges()[half_edge.twin()].twin(), index);
+            assert_eq!(arrangement.half_edges()[half_edge.next()].previous(), index);
+            assert_eq!(arrangement.half_edges()[half_edge.next()].face(), half_edge.face());
+            assert_eq!(arrangement.destination(index), arrangement.half_edges()[half_edge.next()].origin());
+        }
+        let corner = arrangement.vertices().iter().position(|point| *point == Point::new(0, 0).unwrap()).unwrap();
+        assert_eq!(arrangement.outgoing(corner).len(), 2);
+    }
+}
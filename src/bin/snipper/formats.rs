@@ -0,0 +1,359 @@
+use snipper::{AbstractPoint, Path, Point, Polygon};
+use crate::json::{self, Value};
+
+type Ring = Vec<Point>;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Format {
+    Wkt,
+    GeoJson,
+    Points
+}
+impl Format {
+    pub fn parse(name: &str) -> Result<Format, String> {
+        match name {
+            "wkt" => Ok(Format::Wkt),
+            "geojson" => Ok(Format::GeoJson),
+            "points" => Ok(Format::Points),
+            _ => Err(format!("unknown format {}, expected wkt, geojson or points", name))
+        }
+    }
+    // Told by the extension, plain point lists otherwise
+    pub fn of_file(file: &str) -> Format {
+        let extension = std::path::Path::new(file)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("wkt") => Format::Wkt,
+            Some("json") | Some("geojson") => Format::GeoJson,
+            _ => Format::Points
+        }
+    }
+    // Rings of all polygons in the text, closing points dropped
+    pub fn read(&self, text: &str) -> Result<Vec<Path>, String> {
+        let rings = match self {
+            Format::Wkt => read_wkt(text)?,
+            Format::GeoJson => read_geojson(text)?,
+            Format::Points => read_points(text)?
+        };
+        Ok(rings.into_iter().map(|mut ring| {
+            if ring.len() > 1 && ring.first() == ring.last() {
+                ring.pop();
+            }
+            Path::new(&ring)
+        }).collect())
+    }
+    pub fn write(&self, polygon: &Polygon) -> String {
+        match self {
+            Format::Wkt => write_wkt(polygon),
+            Format::GeoJson => write_geojson(polygon),
+            Format::Points => write_points(polygon)
+        }
+    }
+}
+fn point(x: f64, y: f64) -> Result<Point, String> {
+    if x.fract() != 0f64 || y.fract() != 0f64 {
+        return Err(format!("coordinates {} {} are not integers", x, y));
+    }
+    if x.abs() > f64::from(i32::MAX) || y.abs() > f64::from(i32::MAX) {
+        return Err(format!("coordinates {} {} out of bounds", x, y));
+    }
+    Point::new(x as i32, y as i32).map_err(|error| format!("coordinates {} {}: {}", x, y, error))
+}
+
+// One ring per block of lines holding two numbers each, separated
+// by whitespace or a comma. Blocks are separated by empty lines,
+// lines starting with # are left out
+fn read_points(text: &str) -> Result<Vec<Ring>, String> {
+    let mut rings: Vec<Ring> = Vec::new();
+    let mut ring: Ring = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        if line.is_empty() {
+            if !ring.is_empty() {
+                rings.push(std::mem::take(&mut ring));
+            }
+            continue;
+        }
+        let values: Vec<&str> = line.split(|c: char| c == ',' || c.is_whitespace()).filter(|value| !value.is_empty()).collect();
+        let coordinates: Vec<f64> = values.iter().filter_map(|value| value.parse().ok()).collect();
+        if values.len() != 2 || coordinates.len() != 2 {
+            return Err(format!("line {}: expected two numbers", number + 1));
+        }
+        ring.push(point(coordinates[0], coordinates[1]).map_err(|error| format!("line {}: {}", number + 1, error))?);
+    }
+    if !ring.is_empty() {
+        rings.push(ring);
+    }
+    Ok(rings)
+}
+
+#[derive(PartialEq, Clone, Debug)]
+enum Token {
+    Word(String),
+    Number(f64),
+    Open,
+    Close,
+    Comma
+}
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = text.chars().collect();
+    let mut position = 0;
+    while position < chars.len() {
+        let c = chars[position];
+        let start = position;
+        position += 1;
+        match c {
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            ',' => tokens.push(Token::Comma),
+            c if c.is_whitespace() => {},
+            c if c.is_ascii_alphabetic() => {
+                while position < chars.len() && chars[position].is_ascii_alphabetic() {
+                    position += 1;
+                }
+                let word: String = chars[start..position].iter().collect();
+                tokens.push(Token::Word(word.to_ascii_uppercase()));
+            },
+            _ => {
+                while position < chars.len() && "+-0123456789.eE".contains(chars[position]) {
+                    position += 1;
+                }
+                let number: String = chars[start..position].iter().collect();
+                let number = number.parse().map_err(|_| format!("WKT: unexpected {} at character {}", number, start))?;
+                tokens.push(Token::Number(number));
+            }
+        }
+    }
+    Ok(tokens)
+}
+struct WktParser {
+    tokens: Vec<Token>,
+    position: usize
+}
+impl WktParser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(ref token) if *token == expected => Ok(()),
+            token => Err(format!("WKT: expected {:?}, found {:?}", expected, token))
+        }
+    }
+    // Parenthesized list of items separated by commas, or EMPTY
+    fn list<T, F: FnMut(&mut WktParser) -> Result<T, String>>(&mut self, mut item: F) -> Result<Vec<T>, String> {
+        if self.peek() == Some(&Token::Word("EMPTY".to_string())) {
+            self.position += 1;
+            return Ok(Vec::new());
+        }
+        self.expect(Token::Open)?;
+        let mut items = vec![item(self)?];
+        while self.peek() == Some(&Token::Comma) {
+            self.position += 1;
+            items.push(item(self)?);
+        }
+        self.expect(Token::Close)?;
+        Ok(items)
+    }
+    fn point(&mut self) -> Result<Point, String> {
+        match (self.next(), self.next()) {
+            (Some(Token::Number(x)), Some(Token::Number(y))) => point(x, y),
+            _ => Err("WKT: expected a pair of coordinates".to_string())
+        }
+    }
+    fn polygon(&mut self) -> Result<Vec<Ring>, String> {
+        self.list(WktParser::ring)
+    }
+    fn ring(&mut self) -> Result<Ring, String> {
+        self.list(WktParser::point)
+    }
+    fn geometry(&mut self) -> Result<Vec<Ring>, String> {
+        match self.next() {
+            Some(Token::Word(word)) if word == "POLYGON" => self.polygon(),
+            Some(Token::Word(word)) if word == "MULTIPOLYGON" => {
+                Ok(self.list(WktParser::polygon)?.into_iter().flatten().collect())
+            },
+            Some(Token::Word(word)) if word == "GEOMETRYCOLLECTION" => {
+                Ok(self.list(WktParser::geometry)?.into_iter().flatten().collect())
+            },
+            token => Err(format!("WKT: expected a polygon, found {:?}", token))
+        }
+    }
+}
+fn read_wkt(text: &str) -> Result<Vec<Ring>, String> {
+    let mut parser = WktParser { tokens: tokenize(text)?, position: 0 };
+    let mut rings = Vec::new();
+    while parser.peek().is_some() {
+        rings.extend(parser.geometry()?);
+    }
+    Ok(rings)
+}
+
+fn read_geojson(text: &str) -> Result<Vec<Ring>, String> {
+    let mut rings = Vec::new();
+    geojson_rings(&json::parse(text)?, &mut rings)?;
+    Ok(rings)
+}
+fn array(value: Option<&Value>) -> Result<&Vec<Value>, String> {
+    value.and_then(Value::as_array).ok_or_else(|| "GeoJSON: expected an array".to_string())
+}
+fn geojson_rings(value: &Value, rings: &mut Vec<Ring>) -> Result<(), String> {
+    let ring = |value: &Value| -> Result<Ring, String> {
+        array(Some(value))?.iter().map(|position| {
+            let position = array(Some(position))?;
+            match (position.first().and_then(Value::as_number), position.get(1).and_then(Value::as_number)) {
+                (Some(x), Some(y)) => point(x, y),
+                _ => Err("GeoJSON: expected a position".to_string())
+            }
+        }).collect()
+    };
+    match value.get("type").and_then(Value::as_str) {
+        Some("Polygon") => {
+            for value in array(value.get("coordinates"))? {
+                rings.push(ring(value)?);
+            }
+        },
+        Some("MultiPolygon") => {
+            for polygon in array(value.get("coordinates"))? {
+                for value in array(Some(polygon))? {
+                    rings.push(ring(value)?);
+                }
+            }
+        },
+        Some("GeometryCollection") => {
+            for geometry in array(value.get("geometries"))? {
+                geojson_rings(geometry, rings)?;
+            }
+        },
+        Some("Feature") => {
+            if let Some(geometry) = value.get("geometry").filter(|geometry| **geometry != Value::Null) {
+                geojson_rings(geometry, rings)?;
+            }
+        },
+        Some("FeatureCollection") => {
+            for feature in array(value.get("features"))? {
+                geojson_rings(feature, rings)?;
+            }
+        },
+        Some(other) => return Err(format!("GeoJSON: {} holds no polygons", other)),
+        None => return Err("GeoJSON: expected a type".to_string())
+    }
+    Ok(())
+}
+
+// Paths at even levels with the paths right within them, which are holes
+pub fn parts(polygon: &Polygon) -> Vec<(usize, Vec<usize>)> {
+    let records = polygon.structure();
+    let mut parts: Vec<(usize, Vec<usize>)> = Vec::new();
+    let mut part_of: Vec<Option<usize>> = vec![None; records.len()];
+    for (index, record) in records.iter().enumerate() {
+        if record.level() % 2 == 0 {
+            part_of[index] = Some(parts.len());
+            parts.push((index, Vec::new()));
+        }
+    }
+    for (index, record) in records.iter().enumerate() {
+        if record.level() % 2 == 1 {
+            if let Some(part) = record.parent().and_then(|parent| part_of[parent]) {
+                parts[part].1.push(index);
+            }
+        }
+    }
+    parts
+}
+// Closed rings as pairs of coordinates
+fn closed(path: &Path) -> Vec<(i32, i32)> {
+    let mut coordinates: Vec<(i32, i32)> = path.points().iter().map(|point| (point.x().to_int(), point.y().to_int())).collect();
+    if let Some(first) = coordinates.first().copied() {
+        coordinates.push(first);
+    }
+    coordinates
+}
+fn write_wkt(polygon: &Polygon) -> String {
+    let paths = polygon.paths();
+    let parts: Vec<String> = parts(polygon).into_iter().map(|(outer, holes)| {
+        let rings: Vec<String> = std::iter::once(outer).chain(holes).map(|index| {
+            let points: Vec<String> = closed(&paths[index]).iter().map(|(x, y)| format!("{} {}", x, y)).collect();
+            format!("({})", points.join(", "))
+        }).collect();
+        format!("({})", rings.join(", "))
+    }).collect();
+    if parts.is_empty() {
+        "MULTIPOLYGON EMPTY\n".to_string()
+    } else {
+        format!("MULTIPOLYGON ({})\n", parts.join(", "))
+    }
+}
+fn write_geojson(polygon: &Polygon) -> String {
+    let paths = polygon.paths();
+    let parts: Vec<String> = parts(polygon).into_iter().map(|(outer, holes)| {
+        let rings: Vec<String> = std::iter::once(outer).chain(holes).map(|index| {
+            let points: Vec<String> = closed(&paths[index]).iter().map(|(x, y)| format!("[{}, {}]", x, y)).collect();
+            format!("[{}]", points.join(", "))
+        }).collect();
+        format!("[{}]", rings.join(", "))
+    }).collect();
+    format!("{{\"type\": \"MultiPolygon\", \"coordinates\": [{}]}}\n", parts.join(", "))
+}
+fn write_points(polygon: &Polygon) -> String {
+    let rings: Vec<String> = polygon.paths().iter().map(|path| {
+        let lines: Vec<String> = path.points().iter().map(|point| format!("{} {}\n", point.x(), point.y())).collect();
+        lines.concat()
+    }).collect();
+    rings.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Format, parts};
+    use snipper::{AbstractPoint, Path, Snipper};
+
+    fn coordinates(paths: &[Path]) -> Vec<Vec<(i32, i32)>> {
+        paths.iter().map(|path| path.points().iter().map(|point| (point.x().to_int(), point.y().to_int())).collect()).collect()
+    }
+    #[test]
+    fn read_test() {
+        let expected = vec![vec![(0, 0), (10, 0), (10, 10), (0, 10)], vec![(2, 2), (2, 4), (4, 4)], vec![(20, 0), (22, 0), (21, 2)]];
+        let wkt = "GEOMETRYCOLLECTION (MULTIPOLYGON (((0 0, 10 0, 10 10, 0 10, 0 0), (2 2, 2 4, 4 4, 2 2))), polygon((20 0,22 0,21 2)), POLYGON EMPTY)";
+        assert_eq!(coordinates(&Format::Wkt.read(wkt).unwrap()), expected);
+        let geojson = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {}, "geometry": {"type": "MultiPolygon", "coordinates": [[[[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]], [[2, 2], [2, 4], [4, 4]]]]}},
+            {"type": "Feature", "geometry": null},
+            {"type": "Feature", "geometry": {"type": "Polygon", "coordinates": [[[20, 0], [22.0, 0], [21, 2], [20, 0]]]}}
+        ]}"#;
+        assert_eq!(coordinates(&Format::GeoJson.read(geojson).unwrap()), expected);
+        let points = "# square\n0 0\n10, 0\n10 10\n0 10\n\n2 2\n2 4\n4 4\n\n\n20 0\n22 0\n21 2\n20 0\n";
+        assert_eq!(coordinates(&Format::Points.read(points).unwrap()), expected);
+
+        assert!(Format::Wkt.read("POLYGON ((0 0, 1 0.5, 1 1))").is_err());
+        assert!(Format::Wkt.read("POLYGON ((0 0, 1 0, 1 1)").is_err());
+        assert!(Format::GeoJson.read(r#"{"type": "LineString", "coordinates": []}"#).is_err());
+        assert!(Format::Points.read("0 0 0\n").is_err());
+        assert_eq!(Format::of_file("a/b.GeoJSON"), Format::GeoJson);
+        assert_eq!(Format::of_file("b.txt"), Format::Points);
+    }
+    #[test]
+    fn write_test() {
+        let rings = Format::Points.read("0 0\n10 0\n10 10\n0 10\n\n2 2\n4 2\n4 4\n2 4\n\n20 0\n22 0\n21 2\n").unwrap();
+        let polygon = Snipper::normalize(rings).unwrap().canonical().polygon().unwrap();
+        let parts = parts(&polygon);
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts.iter().map(|(_, holes)| holes.len()).sum::<usize>(), 1);
+        for format in [Format::Wkt, Format::GeoJson, Format::Points] {
+            let written = format.write(&polygon);
+            let read = Snipper::normalize(format.read(&written).unwrap()).unwrap().canonical().polygon().unwrap();
+            assert_eq!(coordinates(read.paths()), coordinates(polygon.paths()));
+        }
+    }
+}
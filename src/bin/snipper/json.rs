@@ -0,0 +1,198 @@
+// Just enough of JSON to read GeoJSON geometries
+#[derive(PartialEq, Clone, Debug)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>)
+}
+impl Value {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(members) => members.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+            _ => None
+        }
+    }
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(string) => Some(string),
+            _ => None
+        }
+    }
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        match self {
+            Value::Array(values) => Some(values),
+            _ => None
+        }
+    }
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Value::Number(number) => Some(*number),
+            _ => None
+        }
+    }
+}
+pub fn parse(text: &str) -> Result<Value, String> {
+    let mut parser = Parser { chars: text.chars().collect(), position: 0 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.position < parser.chars.len() {
+        return Err(parser.error("end of input"));
+    }
+    Ok(value)
+}
+struct Parser {
+    chars: Vec<char>,
+    position: usize
+}
+impl Parser {
+    fn error(&self, expected: &str) -> String {
+        format!("JSON: expected {} at character {}", expected, self.position)
+    }
+    fn skip_whitespace(&mut self) {
+        while self.position < self.chars.len() && self.chars[self.position].is_whitespace() {
+            self.position += 1;
+        }
+    }
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.get(self.position).copied()
+    }
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        if self.peek() == Some(expected) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("'{}'", expected)))
+        }
+    }
+    fn value(&mut self) -> Result<Value, String> {
+        match self.peek() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') => Ok(Value::String(self.string()?)),
+            Some('t') => self.literal("true", Value::Bool(true)),
+            Some('f') => self.literal("false", Value::Bool(false)),
+            Some('n') => self.literal("null", Value::Null),
+            Some(_) => self.number(),
+            None => Err(self.error("a value"))
+        }
+    }
+    fn literal(&mut self, word: &str, value: Value) -> Result<Value, String> {
+        let end = self.position + word.len();
+        if end <= self.chars.len() && self.chars[self.position..end].iter().copied().eq(word.chars()) {
+            self.position = end;
+            Ok(value)
+        } else {
+            Err(self.error(word))
+        }
+    }
+    fn number(&mut self) -> Result<Value, String> {
+        let start = self.position;
+        while self.position < self.chars.len() && "+-0123456789.eE".contains(self.chars[self.position]) {
+            self.position += 1;
+        }
+        let text: String = self.chars[start..self.position].iter().collect();
+        text.parse().map(Value::Number).map_err(|_| {
+            self.position = start;
+            self.error("a number")
+        })
+    }
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut string = String::new();
+        loop {
+            let next = self.chars.get(self.position).copied();
+            self.position += 1;
+            match next {
+                Some('"') => return Ok(string),
+                Some('\\') => {
+                    let escaped = self.chars.get(self.position).copied();
+                    self.position += 1;
+                    match escaped {
+                        Some('b') => string.push('\u{8}'),
+                        Some('f') => string.push('\u{c}'),
+                        Some('n') => string.push('\n'),
+                        Some('r') => string.push('\r'),
+                        Some('t') => string.push('\t'),
+                        Some('u') => {
+                            let end = (self.position + 4).min(self.chars.len());
+                            let digits: String = self.chars[self.position..end].iter().collect();
+                            let code = u32::from_str_radix(&digits, 16).map_err(|_| self.error("four hex digits"))?;
+                            string.push(std::char::from_u32(code).unwrap_or('\u{fffd}'));
+                            self.position = end;
+                        },
+                        Some(other) => string.push(other),
+                        None => return Err(self.error("an escaped character"))
+                    }
+                },
+                Some(other) => string.push(other),
+                None => return Err(self.error("'\"'"))
+            }
+        }
+    }
+    fn array(&mut self) -> Result<Value, String> {
+        self.expect('[')?;
+        let mut values = Vec::new();
+        if self.peek() == Some(']') {
+            self.position += 1;
+            return Ok(Value::Array(values));
+        }
+        loop {
+            values.push(self.value()?);
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some(']') => {
+                    self.position += 1;
+                    return Ok(Value::Array(values));
+                },
+                _ => return Err(self.error("',' or ']'"))
+            }
+        }
+    }
+    fn object(&mut self) -> Result<Value, String> {
+        self.expect('{')?;
+        let mut members = Vec::new();
+        if self.peek() == Some('}') {
+            self.position += 1;
+            return Ok(Value::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            let name = self.string()?;
+            self.expect(':')?;
+            members.push((name, self.value()?));
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some('}') => {
+                    self.position += 1;
+                    return Ok(Value::Object(members));
+                },
+                _ => return Err(self.error("',' or '}'"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse, Value};
+
+    #[test]
+    fn parse_test() {
+        let value = parse(r#" {"type": "Polygon", "coordinates": [[[0, 0], [1e1, -2.5]]], "name": "a\"b\u0041", "ok": [true, null]} "#).unwrap();
+        assert_eq!(value.get("type").and_then(Value::as_str), Some("Polygon"));
+        let coordinates = value.get("coordinates").and_then(Value::as_array).unwrap();
+        assert_eq!(coordinates[0], Value::Array(vec![
+            Value::Array(vec![Value::Number(0.0), Value::Number(0.0)]),
+            Value::Array(vec![Value::Number(10.0), Value::Number(-2.5)])
+        ]));
+        assert_eq!(value.get("name").and_then(Value::as_str), Some("a\"bA"));
+        assert_eq!(value.get("ok"), Some(&Value::Array(vec![Value::Bool(true), Value::Null])));
+        assert!(parse("[1, 2").is_err());
+        assert!(parse("{\"a\" 1}").is_err());
+        assert!(parse("[1] x").is_err());
+    }
+}
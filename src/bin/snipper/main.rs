@@ -0,0 +1,163 @@
+extern crate snipper;
+
+mod formats;
+mod json;
+
+use snipper::{Snipper, Solution, Polygon, Path, Operation};
+use crate::formats::{Format, parts};
+use std::process;
+
+const USAGE: &str = "\
+Usage:
+    snipper (union | intersection | difference | xor) <subject> <clipping> [options]
+    snipper normalize <file> [options]
+
+Operand files hold polygons as WKT, GeoJSON or point lists, one point
+a line and rings separated by empty lines. Coordinates must be integers.
+
+Options:
+    --input <format>     wkt, geojson or points, told by the extension otherwise
+    --output <format>    wkt, geojson or points, wkt by default
+    --to <file>          where to write the result, standard output by default
+    --canonical          canonical order and orientation of the rings
+    --strict             valid as OGC simple features
+    --without-cleanup    rings as drawn, with collinear points
+    --quiet              no statistics on standard error
+";
+
+const OPERATIONS: [&str; 5] = ["union", "intersection", "difference", "xor", "normalize"];
+
+struct Options {
+    operation: String,
+    files: Vec<String>,
+    input: Option<Format>,
+    output: Format,
+    to: Option<String>,
+    canonical: bool,
+    strict: bool,
+    without_cleanup: bool,
+    quiet: bool
+}
+impl Options {
+    fn parse(args: &[String]) -> Result<Options, String> {
+        let mut options = Options {
+            operation: String::new(),
+            files: Vec::new(),
+            input: None,
+            output: Format::Wkt,
+            to: None,
+            canonical: false,
+            strict: false,
+            without_cleanup: false,
+            quiet: false
+        };
+        let mut positional: Vec<String> = Vec::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| args.next().cloned().ok_or_else(|| format!("{} needs a value", name));
+            match arg.as_str() {
+                "--input" => options.input = Some(Format::parse(&value(arg)?)?),
+                "--output" => options.output = Format::parse(&value(arg)?)?,
+                "--to" => options.to = Some(value(arg)?),
+                "--canonical" => options.canonical = true,
+                "--strict" => options.strict = true,
+                "--without-cleanup" => options.without_cleanup = true,
+                "--quiet" => options.quiet = true,
+                _ if arg.starts_with("--") => return Err(format!("unknown option {}", arg)),
+                _ => positional.push(arg.clone())
+            }
+        }
+        if positional.is_empty() {
+            return Err("missing operation".to_string());
+        }
+        options.operation = positional.remove(0);
+        options.files = positional;
+        if !OPERATIONS.contains(&options.operation.as_str()) {
+            return Err(format!("unknown operation {}", options.operation));
+        }
+        let operands = if options.operation == "normalize" { 1 } else { 2 };
+        if options.files.len() != operands {
+            return Err(format!("{} takes {} operand files, got {}", options.operation, operands, options.files.len()));
+        }
+        Ok(options)
+    }
+    fn read(&self, file: &str) -> Result<Vec<Path>, String> {
+        let text = std::fs::read_to_string(file).map_err(|error| format!("{}: {}", file, error))?;
+        let format = self.input.unwrap_or_else(|| Format::of_file(file));
+        format.read(&text).map_err(|error| format!("{}: {}", file, error))
+    }
+    // Operands are normalized first, their rings may come in any order and orientation
+    fn operand(&self, file: &str) -> Result<Polygon, String> {
+        let paths = self.read(file)?;
+        Snipper::normalize(paths)
+            .and_then(Solution::polygon)
+            .map_err(|error| format!("{}: {}", file, error))
+    }
+    fn solution(&self) -> Result<Solution, String> {
+        let operation: &'static Operation = match self.operation.as_str() {
+            "union" => &Operation::UNION,
+            "intersection" => &Operation::INTERSECTION,
+            "difference" => &Operation::DIFFERENCE,
+            "xor" => &Operation::XOR,
+            _ => return Snipper::normalize(self.read(&self.files[0])?).map_err(|error| error.to_string())
+        };
+        let (subject, clipping) = (self.operand(&self.files[0])?, self.operand(&self.files[1])?);
+        Snipper::perform_operation(subject, clipping, operation).map_err(|error| error.to_string())
+    }
+}
+
+fn statistics(polygon: &Polygon) -> String {
+    let records = polygon.structure();
+    let holes = records.iter().filter(|record| record.level() % 2 == 1).count();
+    let depth = records.iter().map(|record| record.level() + 1).max().unwrap_or(0);
+    let mut lines = vec![
+        format!("area: {}", polygon.area()),
+        format!("paths: {} ({} outer, {} holes)", records.len(), records.len() - holes, holes),
+        format!("parts: {}", parts(polygon).len()),
+        format!("nesting depth: {}", depth)
+    ];
+    for (index, (path, record)) in polygon.paths().iter().zip(records).enumerate() {
+        let parent = record.parent().map_or("-".to_string(), |parent| parent.to_string());
+        lines.push(format!(
+            "path {}: level {}, parent {}, {} points, area {}",
+            index, record.level(), parent, path.points().len(), path.area()
+        ));
+    }
+    lines.join("\n")
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let options = Options::parse(args).map_err(|error| format!("{}\n\n{}", error, USAGE))?;
+    let mut solution = options.solution()?;
+    if options.canonical {
+        solution = solution.canonical();
+    }
+    if options.strict {
+        solution = solution.strict();
+    }
+    if options.without_cleanup {
+        solution = solution.without_cleanup();
+    }
+    let polygon = solution.polygon().map_err(|error| error.to_string())?;
+    let output = options.output.write(&polygon);
+    match &options.to {
+        Some(file) => std::fs::write(file, output).map_err(|error| format!("{}: {}", file, error))?,
+        None => print!("{}", output)
+    }
+    if !options.quiet {
+        eprintln!("{}", statistics(&polygon));
+    }
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        print!("{}", USAGE);
+        return;
+    }
+    if let Err(error) = run(&args) {
+        eprintln!("snipper: {}", error);
+        process::exit(1);
+    }
+}
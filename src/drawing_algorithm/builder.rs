@@ -2,6 +2,7 @@ use crate::{AbstractPoint, Point, Path, PathBuilder};
 use std::cmp::Ordering;
 use crate::drawing_algorithm::routes::Routes;
 use std::collections::btree_set::BTreeSet;
+use crate::error::Error;
 
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
 pub enum BuilderIndex {
@@ -60,7 +61,12 @@ pub enum Location {
 pub struct PathsInConstruction {
     head: BuilderIndex,
     nodes: Vec<ChainBuilderNode>,
-    structure: Vec<BuilderIndex>
+    structure: Vec<BuilderIndex>,
+    groups: Vec<usize>,
+    open_ends: Vec<usize>,
+    completed: Vec<usize>,
+    // Slots of the chains of paths already taken, reused by new chains
+    free: Vec<usize>
 }
 impl PathsInConstruction {
     pub fn inspect(&self) -> String {
@@ -74,16 +80,23 @@ impl PathsInConstruction {
         PathsInConstruction{
             head: BuilderIndex::BeforeFirst,
             nodes: Vec::new(),
-            structure: Vec::new()
+            structure: Vec::new(),
+            groups: Vec::new(),
+            open_ends: Vec::new(),
+            completed: Vec::new(),
+            free: Vec::new()
         }
     }
     pub fn insert_after(&mut self, builder: ChainBuilder, index: BuilderIndex) -> BuilderIndex {
-        let new_index = self.nodes.len();
+        let new_index = self.free.last().copied().unwrap_or(self.nodes.len());
         let new_node = match index {
             BuilderIndex::BeforeFirst => {
                 let old_head = self.head;
                 self.head = BuilderIndex::Some(new_index);
-                ChainBuilderNode::new(builder, new_index, old_head.option())
+                if let Some(old_head) = old_head.option() {
+                    self.nodes[old_head].previous = Some(new_index);
+                }
+                ChainBuilderNode::new(builder, new_index, None, old_head.option())
             },
             BuilderIndex::Some(old_index) => {
                 self.create_node_after_index(builder, new_index, &old_index)
@@ -92,16 +105,48 @@ impl PathsInConstruction {
                 panic!("Expected index or before first position, got after last");
             }
         };
-        debug_assert!(self.nodes.len() == self.structure.len());
-        self.structure.push(index);
-        self.nodes.push(new_node);
+        self.place(new_node, index, new_index, 2);
         BuilderIndex::Some(new_index)
     }
     fn create_node_after_index(&mut self, builder: ChainBuilder, new_index: usize, old_index: &usize) -> ChainBuilderNode {
-        let mut previous_node: &mut ChainBuilderNode = &mut self.nodes[*old_index];
+        let previous_node: &mut ChainBuilderNode = &mut self.nodes[*old_index];
         let next = previous_node.next();
         previous_node.next = Some(new_index);
-        ChainBuilderNode::new(builder, new_index, next)
+        if let Some(next) = next {
+            self.nodes[next].previous = Some(new_index);
+        }
+        ChainBuilderNode::new(builder, new_index, Some(*old_index), next)
+    }
+    // The node goes to a free slot if there is one, taking its index
+    fn place(&mut self, node: ChainBuilderNode, link: BuilderIndex, group: usize, open_ends: usize) {
+        debug_assert!(self.nodes.len() == self.structure.len());
+        let index = node.index;
+        if self.free.pop().is_some() {
+            self.nodes[index] = node;
+            self.structure[index] = link;
+            self.groups[index] = group;
+            self.open_ends[index] = open_ends;
+        } else {
+            self.nodes.push(node);
+            self.structure.push(link);
+            self.groups.push(group);
+            self.open_ends.push(open_ends);
+        }
+    }
+    // Takes the node out of the list and frees its slot. Links to it
+    // are no longer told right, which only paths built at once need
+    fn remove(&mut self, index: usize) {
+        let node = &mut self.nodes[index];
+        let (previous, next) = (node.previous.take(), node.next.take());
+        node.child.chain = Vec::new();
+        match previous {
+            Some(previous) => self.nodes[previous].next = next,
+            None => self.head = next.map_or(BuilderIndex::BeforeFirst, BuilderIndex::Some)
+        }
+        if let Some(next) = next {
+            self.nodes[next].previous = previous;
+        }
+        self.free.push(index);
     }
     #[cfg(test)]
     fn head_index(&self) -> BuilderIndex {
@@ -135,59 +180,86 @@ impl PathsInConstruction {
             None
         }
     }
-    pub fn build_paths(mut self) -> (Vec<Path>, Routes) {
+    pub fn build_paths(mut self) -> Result<(Vec<Path>, Routes), Error> {
         let mut paths = Vec::new();
         let mut routes = Routes::new(self.structure.drain(..).collect());
         let mut visited: BTreeSet<usize> = BTreeSet::new();
         for node in &self.nodes {
-            if !visited.contains(&node.index) {
-                let mut next = Some(node);
-                let mut previous: Option<usize> = None;
-                let mut path_builder = PathBuilder::new();
-                let path_index = paths.len();
-                while let Some(node) = next {
-                    visited.insert(node.index);
-                    routes.add_chain_to_path(node.index, path_index);
-
-                    let connection = if let Some(previous) = previous {
-                        if node.end_connection.unwrap() == previous {
-                            Location::Start
-                        } else if node.start_connection.unwrap() == previous {
-                            Location::End
-                        } else {
-                            panic!("Expected connection to previous");
-                        }
-                    } else {
-                        Location::End
-                    };
-                    let tuple = self.trace(
-                        &node.child,
-                        connection,
-                        path_builder,
-                        routes
-                    );
-                    path_builder = tuple.0;
-                    routes = tuple.1;
-
-                    let candidate = match connection {
-                        Location::End => node.end_connection.unwrap(),
-                        Location::Start => node.start_connection.unwrap()
-                    };
-                    previous = Some(node.index);
-                    next = if visited.contains(&candidate) {
-                        None
-                    } else {
-                        let next_chain = Some(&self.nodes[candidate]);
-                        next_chain
-                    };
-                }
-                let path = path_builder.build();
-                if !path.is_null() {
-                    paths.push(path);
-                }
+            // Chains of the paths already taken are left empty
+            if visited.contains(&node.index) || node.child.chain.is_empty() {
+                continue;
+            }
+            let mut path_builder = PathBuilder::new();
+            let path_index = paths.len();
+            for (index, connection) in self.ring(node.index)? {
+                visited.insert(index);
+                routes.add_chain_to_path(index, path_index);
+                self.trace(&self.nodes[index].child, connection, &mut path_builder);
+            }
+            let path = path_builder.build();
+            if !path.is_null() {
+                paths.push(path);
+            }
+        }
+        Ok((paths, routes))
+    }
+    // Paths whose chains got connected all around since the last call,
+    // the slots of their chains are freed for new ones
+    pub fn take_paths(&mut self) -> Result<Vec<Path>, Error> {
+        let mut paths = Vec::new();
+        for start in std::mem::take(&mut self.completed) {
+            let mut path_builder = PathBuilder::new();
+            for (index, connection) in self.ring(start)? {
+                self.trace(&self.nodes[index].child, connection, &mut path_builder);
+                self.remove(index);
+            }
+            let path = path_builder.build();
+            if !path.is_null() {
+                paths.push(path);
             }
         }
-        (paths, routes)
+        Ok(paths)
+    }
+    // Chains of the path going through the given one, in order, each
+    // with the end it is traced towards
+    fn ring(&self, start: usize) -> Result<Vec<(usize, Location)>, Error> {
+        let mut ring = Vec::new();
+        let mut next = Some(start);
+        let mut previous: Option<usize> = None;
+        while let Some(index) = next {
+            let node = &self.nodes[index];
+            let connection = if let Some(previous) = previous {
+                if node.end_connection == Some(previous) {
+                    Location::Start
+                } else if node.start_connection == Some(previous) {
+                    Location::End
+                } else {
+                    return Err(Error::FatalError(
+                        format!("Chain {} not connected to chain {}", node.index, previous)
+                    ));
+                }
+            } else {
+                Location::End
+            };
+            ring.push((index, connection));
+            let candidate = match connection {
+                Location::End => node.end_connection,
+                Location::Start => node.start_connection
+            };
+            let candidate = match candidate {
+                Some(candidate) => candidate,
+                None => return Err(Error::FatalError(
+                    format!("Chain {} left open", node.index)
+                ))
+            };
+            previous = Some(index);
+            next = if candidate == start {
+                None
+            } else {
+                Some(candidate)
+            };
+        }
+        Ok(ring)
     }
     pub fn connect(&mut self, a: usize, b: usize, location_a: Location, location_b: Location) {
         {
@@ -198,35 +270,43 @@ impl PathsInConstruction {
             let node_b = &mut self.nodes[b];
             ChainBuilderNode::connect(node_b, a, location_b);
         }
+        let (group_a, group_b) = (self.group(a), self.group(b));
+        if group_a != group_b {
+            self.groups[group_b] = group_a;
+            self.open_ends[group_a] += self.open_ends[group_b];
+        }
+        self.open_ends[group_a] -= 2;
+        if self.open_ends[group_a] == 0 {
+            self.completed.push(a);
+        }
+    }
+    // Chains connected to each other so far share a group,
+    // which counts the ends still left open
+    fn group(&mut self, mut index: usize) -> usize {
+        while self.groups[index] != index {
+            self.groups[index] = self.groups[self.groups[index]];
+            index = self.groups[index];
+        }
+        index
     }
     fn trace(
         &self,
         chain: &ChainBuilder,
         towards: Location,
-        mut path: PathBuilder,
-        routes: Routes
-    ) -> (PathBuilder, Routes) {
+        path: &mut PathBuilder
+    ) {
         match towards {
             Location::End => {
-                for (_, point) in chain.chain
-                    .iter()
-                    .take(chain.chain.len() - 1)
-                    .enumerate() {
+                for point in chain.chain.iter().take(chain.chain.len() - 1) {
                     path.add(point);
                 }
             },
             Location::Start => {
-                for (_, point) in chain.chain
-                    .iter()
-                    .rev()
-                    .take(chain.chain.len() - 1)
-                    .enumerate() {
+                for point in chain.chain.iter().rev().take(chain.chain.len() - 1) {
                     path.add(point);
                 }
             }
         }
-
-        (path, routes)
     }
     fn to_vec(&self) -> Vec<&ChainBuilderNode> {
         let mut vec = Vec::new();
@@ -241,18 +321,21 @@ impl PathsInConstruction {
 pub struct ChainBuilderNode {
     child: ChainBuilder,
     index: usize,
+    previous: Option<usize>,
     next: Option<usize>,
     start_connection: Option<usize>,
     end_connection: Option<usize>
 }
 impl ChainBuilderNode {
-    fn new (child: ChainBuilder, index: usize, next: Option<usize>) -> ChainBuilderNode {
+    fn new (child: ChainBuilder, index: usize, previous: Option<usize>, next: Option<usize>) -> ChainBuilderNode {
         ChainBuilderNode{
             child,
             index,
+            previous,
             next,
             start_connection: None,
-            end_connection: None }
+            end_connection: None
+        }
     }
     fn connect(a: &mut ChainBuilderNode, b_index: usize, location: Location) {
         if location == Location::Start {
@@ -306,7 +389,7 @@ impl ChainBuilder {
 #[cfg(test)]
 mod test {
     use crate::{Point};
-    use crate::drawing_algorithm::builder::{ChainBuilder, PathsInConstruction, BuilderIndex};
+    use crate::drawing_algorithm::builder::{ChainBuilder, PathsInConstruction, BuilderIndex, Location};
 
     #[test]
     fn list_mutable_access_test() {
@@ -362,4 +445,51 @@ mod test {
         pb.append(p1.clone());
         assert_eq!(pb.loose_end(), &p1);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn take_paths_test() {
+        let point = |x: i32, y: i32| Point::new(x, y).expect("!");
+        let mut pic = PathsInConstruction::new();
+        let lower = pic.insert_after(ChainBuilder::new(&point(0, 0)), BuilderIndex::BeforeFirst).unwrap();
+        let upper = pic.insert_after(ChainBuilder::new(&point(0, 0)), BuilderIndex::Some(lower)).unwrap();
+        pic.insert_after(ChainBuilder::new(&point(5, 5)), BuilderIndex::Some(upper));
+        pic.connect(lower, upper, Location::Start, Location::Start);
+        pic.get_builder_mut(lower).unwrap().append(point(2, 0));
+        pic.get_builder_mut(lower).unwrap().append(point(2, 2));
+        pic.get_builder_mut(upper).unwrap().append(point(2, 2));
+        assert!(pic.take_paths().unwrap().is_empty());
+        pic.connect(lower, upper, Location::End, Location::End);
+        let paths = pic.take_paths().unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].points(), &vec![point(0, 0), point(2, 0), point(2, 2)]);
+        assert!(pic.take_paths().unwrap().is_empty());
+        // The chain left open is still there
+        assert!(pic.build_paths().is_err());
+    }
+    #[test]
+    fn take_paths_bounded_test() {
+        let point = |x: i32, y: i32| Point::new(x, y).expect("!");
+        let mut pic = PathsInConstruction::new();
+        let mut current = BuilderIndex::BeforeFirst;
+        for offset in (0..100).map(|index| index * 10) {
+            let lower = pic.insert_after(ChainBuilder::new(&point(offset, 0)), current).unwrap();
+            let upper = pic.insert_after(ChainBuilder::new(&point(offset, 0)), BuilderIndex::Some(lower)).unwrap();
+            current = BuilderIndex::Some(upper);
+            pic.connect(lower, upper, Location::Start, Location::Start);
+            // Passing the middle twice
+            for (x, y) in [(1, -1), (2, 0), (3, -1), (4, 0)] {
+                pic.get_builder_mut(lower).unwrap().append(point(offset + x, y));
+                pic.get_builder_mut(upper).unwrap().append(point(offset + x, -y));
+            }
+            pic.connect(lower, upper, Location::End, Location::End);
+            let paths = pic.take_paths().unwrap();
+            assert_eq!(paths.len(), 1);
+            assert_eq!(paths[0].points().len(), 8);
+        }
+        assert_eq!(pic.nodes.len(), 2);
+        assert_eq!(pic.structure.len(), pic.nodes.len());
+        assert_eq!(pic.groups.len(), pic.nodes.len());
+        assert_eq!(pic.open_ends.len(), pic.nodes.len());
+        assert_eq!(pic.head_index(), BuilderIndex::BeforeFirst);
+    }
+}
@@ -1,8 +1,9 @@
 use crate::drawing_algorithm::builder_list::{BuilderList, Chunk};
 use crate::drawing_algorithm::builder::{PathsInConstruction, BuilderIndex, ChainBuilder, Location};
-use crate::{Coordinate, Point, AbstractPoint};
+use crate::{Coordinate, Point, AbstractPoint, Path};
 use crate::edge::Edge;
 use crate::units::Pseudoangle;
+use crate::error::Error;
 use std::collections::btree_map::BTreeMap;
 
 pub struct DrawingAlgorithm {
@@ -29,30 +30,39 @@ impl DrawingAlgorithm {
         let candidates = BuilderList::new();
         DrawingAlgorithm::new(x, state, candidates)
     }
-    pub fn next_state(self, new_x: Coordinate) -> DrawingAlgorithm {
+    pub fn next_state(self, new_x: Coordinate) -> Result<DrawingAlgorithm, Error> {
         debug_assert!(self.created.is_empty());
         let old_x = self.x();
         let (terminated, passed) = self.candidates.split_off(old_x);
         let candidates = passed.extend(self.promises);
-        let state = DrawingAlgorithm::terminate(terminated, self.state);
-        DrawingAlgorithm::new(new_x, state, candidates)
+        let state = DrawingAlgorithm::terminate(terminated, self.state, old_x)?;
+        Ok(DrawingAlgorithm::new(new_x, state, candidates))
     }
-    pub fn terminate_all(self) -> PathsInConstruction {
+    pub fn terminate_all(self) -> Result<PathsInConstruction, Error> {
         debug_assert!(self.created.is_empty());
-        DrawingAlgorithm::terminate(self.candidates, self.state)
+        let x = self.x();
+        DrawingAlgorithm::terminate(self.candidates, self.state, x)
     }
-    fn terminate(terminated: BuilderList, mut state: PathsInConstruction) -> PathsInConstruction {
+    fn terminate(
+        terminated: BuilderList,
+        mut state: PathsInConstruction,
+        x: Coordinate
+    ) -> Result<PathsInConstruction, Error> {
         for chunk in terminated.chunks() {
             match chunk {
                 Chunk::Complete(a, b) => {
                     state.connect(a, b, Location::End, Location::End);
                 },
                 Chunk::Partial(_, point) => {
-                    panic!("Partial chunk at {}", point.inspect());
+                    return Err(Error::UnmatchedChainError { point: point.clone(), x });
                 }
             }
         }
-        state
+        Ok(state)
+    }
+    // Paths closed by the chains terminated so far
+    pub fn take_paths(&mut self) -> Result<Vec<Path>, Error> {
+        self.state.take_paths()
     }
     pub fn x(&self) -> Coordinate {
         self.x
@@ -129,6 +139,9 @@ mod test {
     use crate::edge::Edge;
     use crate::operation::Operand;
     use crate::drawing_algorithm::builder::BuilderIndex;
+    use crate::drawing_algorithm::builder::PathsInConstruction;
+    use crate::drawing_algorithm::builder_list::BuilderList;
+    use crate::error::Error;
 
     #[test]
     fn create_builder_test() {
@@ -166,7 +179,7 @@ mod test {
         state.draw_edge(&e2);
         assert_eq!(state.candidates.points().get(&p1).unwrap().iter().peekable().peek(), Some(&&0));
         assert_eq!(state.promises.points().get(&p).unwrap().iter().peekable().peek(), Some(&&1));
-        let mut state = state.next_state(Coordinate::new(10));
+        let mut state = state.next_state(Coordinate::new(10)).unwrap();
         assert_eq!(state.candidates.points().get(&p1).unwrap().iter().peekable().peek(), Some(&&0));
         assert_eq!(state.candidates.points().get(&p).unwrap().iter().peekable().peek(), Some(&&1));
         let e3 = Edge::original(0, Operand::Subject, &p1, &p2).unwrap();
@@ -175,8 +188,8 @@ mod test {
         state.draw_edge(&e4);
         assert_eq!(state.candidates.points().get(&p2).unwrap().iter().peekable().peek(), Some(&&0));
         assert_eq!(state.candidates.points().get(&p2).unwrap().iter().peekable().nth(1), Some(&1));
-        let pic = state.terminate_all();
-        let (paths, routes) = pic.build_paths();
+        let pic = state.terminate_all().unwrap();
+        let (paths, routes) = pic.build_paths().unwrap();
         assert_eq!(paths.len(), 1);
         assert_eq!(paths[0].points().len(), 3);
         assert!(routes.belongs_to_path(&0, &0));
@@ -184,4 +197,18 @@ mod test {
         assert_eq!(routes.structure()[0], BuilderIndex::BeforeFirst);
         assert_eq!(routes.structure()[1], BuilderIndex::Some(0));
     }
-}
\ No newline at end of file
+    #[test]
+    fn unmatched_chain_test() {
+        let p = Point::new(-10, 0).unwrap();
+        let mut list = BuilderList::new();
+        list.push_back(p.clone(), 0);
+        let state = PathsInConstruction::new();
+        match DrawingAlgorithm::terminate(list, state, Coordinate::new(0)) {
+            Err(Error::UnmatchedChainError { point, x }) => {
+                assert_eq!(point, p);
+                assert_eq!(x, Coordinate::new(0));
+            },
+            _ => panic!("Unmatched chain error expected")
+        }
+    }
+}
@@ -20,13 +20,18 @@ impl Partition {
             false => self.previous_for_odd()
         }
     }
-    pub fn adjacent_for_even(position: Position) -> &'static Partition {
-        match position {
-            Position::Out  => &Partition::OUT_OUT,
-            Position::In => &Partition::IN_IN,
-            _ => panic!("Unexpected position: {:?}", position)
+    // Partitions only ever carry In or Out, anything else is treated as Out
+    pub fn from_positions(counterclockwise: Position, clockwise: Position) -> &'static Partition {
+        match (counterclockwise, clockwise) {
+            (Position::In, Position::In) => &Partition::IN_IN,
+            (Position::In, _) => &Partition::IN_OUT,
+            (_, Position::In) => &Partition::OUT_IN,
+            _ => &Partition::OUT_OUT
         }
     }
+    pub fn adjacent_for_even(position: Position) -> &'static Partition {
+        Partition::from_positions(position, position)
+    }
     pub fn previous_for_even(&self) -> &'static Partition {
         let previous = self.counterclockwise;
         Partition::adjacent_for_even(previous)
@@ -34,19 +39,12 @@ impl Partition {
     pub fn previous_for_odd(&self) -> &'static Partition {
         let previous = self.counterclockwise;
         match previous {
-            Position::Out  => &Partition::IN_OUT,
             Position::In => &Partition::OUT_IN,
-            _ => panic!("Unexpected position: {:?}", self.counterclockwise)
+            _ => &Partition::IN_OUT
         }
     }
     pub fn flip(&self) -> &'static Partition {
-        match self {
-            &Partition::IN_IN => &Partition::IN_IN,
-            &Partition::IN_OUT => &Partition::OUT_IN,
-            &Partition::OUT_IN => &Partition::IN_OUT,
-            &Partition::OUT_OUT => &Partition::OUT_OUT,
-            _ => panic!("Unknown partition type: {:?}", self)
-        }
+        Partition::from_positions(self.clockwise, self.counterclockwise)
     }
     pub fn position_from_partition(
         proper: &Partition,
@@ -78,12 +76,12 @@ impl Partition {
     }
     #[allow(dead_code)]
     pub fn inspect(&self) -> String {
-        match self {
-            &Partition::IN_IN => "IN-IN".to_owned(),
-            &Partition::IN_OUT => "IN-OUT".to_owned(),
-            &Partition::OUT_IN => "OUT-IN".to_owned(),
-            &Partition::OUT_OUT => "OUT-OUT".to_owned(),
-            _ => panic!("Unknown partition type: {:?}", self)
+        match *self {
+            Partition::IN_IN => "IN-IN".to_owned(),
+            Partition::IN_OUT => "IN-OUT".to_owned(),
+            Partition::OUT_IN => "OUT-IN".to_owned(),
+            Partition::OUT_OUT => "OUT-OUT".to_owned(),
+            _ => format!("{:?}-{:?}", self.counterclockwise, self.clockwise)
         }
     }
     pub const IN_IN: Partition = Partition{counterclockwise: Position::In, clockwise: Position::In};
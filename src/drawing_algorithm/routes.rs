@@ -7,11 +7,11 @@ pub enum FirstIndex {
     Confirmed(usize)
 }
 impl FirstIndex {
-    pub fn expect(&self, msg: &'static str) -> usize {
+    pub fn index(&self) -> Option<usize> {
         match self {
-            FirstIndex::Unkwnown => panic!(msg),
-            FirstIndex::Random(index) => *index,
-            FirstIndex::Confirmed(index) => *index
+            FirstIndex::Unkwnown => None,
+            FirstIndex::Random(index) => Some(*index),
+            FirstIndex::Confirmed(index) => Some(*index)
         }
     }
     pub fn is_none(&self) -> bool {
@@ -40,8 +40,10 @@ impl Routes {
         self.paths[*path_index] = FirstIndex::Confirmed(index)
     }
     pub fn first(&self, path_index: &usize) -> FirstIndex {
-        self.paths.get(*path_index).expect("Path not there").clone()
-
+        match self.paths.get(*path_index) {
+            Some(first) => *first,
+            None => FirstIndex::Unkwnown
+        }
     }
     pub fn add_chain_to_path(&mut self, chain_index: usize, path_index: usize) {
         if self.chains.len() <= chain_index {
@@ -57,7 +59,7 @@ impl Routes {
         }
     }
     pub fn belongs_to_path(&self, chain_index: &usize, candidate: &usize) -> bool {
-        if let Some(path_index) = &self.chains[*chain_index] {
+        if let Some(Some(path_index)) = self.chains.get(*chain_index) {
             path_index == candidate
         } else {
             false
@@ -39,12 +39,9 @@ impl Edge {
             (start, end)
         };
         let straight = Straight::new(start, end);
-        if straight.is_null() {
-            None
-        } else {
-            let pseudoangle = straight.vector().pseudoangle().unwrap();
-            Some(Edge { index, straight, subject, clipping, pseudoangle })
-        }
+        straight.vector().pseudoangle().map(|pseudoangle| {
+            Edge { index, straight, subject, clipping, pseudoangle }
+        })
     }
     pub fn count(&self, operand: Option<Operand>) -> usize {
         match operand {
@@ -8,14 +8,21 @@ use crate::units::Coordinate;
 use crate::error::Error;
 use priority_queue::PriorityQueue;
 use std::cmp::Ordering;
+use std::borrow::Borrow;
 use crate::operation::Operand;
+use crate::intersection_points::{Lineage, SegmentId};
 
 pub trait AbstractQueue {
-    fn pop_edge(&mut self, at: Coordinate) -> Option<Edge>;
+    fn pop_edge(&mut self, at: Coordinate) -> Result<Option<Edge>, Error>;
     fn push_edge(&mut self, edge: Edge);
     fn next_x(&self) -> Option<Coordinate>;
     fn create_edge(&mut self, start: &Point, end: &Point, operand: Operand) -> Result<Edge, Error>;
     fn next_edge_index(&mut self) -> Result<usize, Error>;
+    // Called whenever an edge is snipped at the point, the part
+    // right of it getting the new index
+    fn record_split(&mut self, _edge: &Edge, _right_index: usize, _point: &Point) {}
+    // Called whenever an identical edge is merged into the edge
+    fn record_merge(&mut self, _edge: &Edge, _merged: &Edge) {}
 }
 #[derive(PartialEq, Eq, Debug)]
 pub struct Priority {
@@ -44,20 +51,25 @@ impl Ord for Priority {
 }
 pub struct Queue {
     num_edges: usize,
-    queue: PriorityQueue<Edge, Priority>
+    queue: PriorityQueue<Edge, Priority>,
+    lineage: Option<Lineage>
 }
 impl AbstractQueue for Queue {
-    fn pop_edge(&mut self, at: Coordinate) -> Option<Edge> {
+    fn pop_edge(&mut self, at: Coordinate) -> Result<Option<Edge>, Error> {
         if let Some((edge, priority)) = self.queue.peek() {
             if priority.x < at {
-                panic!("Not supposed to be there at {}: {}", at, edge.inspect());
+                Err(Error::MisplacedEdgeError {
+                    edge: edge.index,
+                    point: edge.upper_left().clone(),
+                    x: at
+                })
             } else if priority.x == at {
-                Some(self.queue.pop().unwrap().0)
+                Ok(Some(self.queue.pop().unwrap().0))
             } else {
-                None
+                Ok(None)
             }
         } else {
-            None
+            Ok(None)
         }
     }
     fn push_edge(&mut self, edge: Edge) {
@@ -89,6 +101,16 @@ impl AbstractQueue for Queue {
             Ok(edge_index)
         }
     }
+    fn record_split(&mut self, edge: &Edge, right_index: usize, point: &Point) {
+        if let Some(lineage) = self.lineage.as_mut() {
+            lineage.record_split(edge, right_index, point);
+        }
+    }
+    fn record_merge(&mut self, edge: &Edge, merged: &Edge) {
+        if let Some(lineage) = self.lineage.as_mut() {
+            lineage.record_merge(edge, merged);
+        }
+    }
 }
 
 impl Queue {
@@ -104,58 +126,80 @@ impl Queue {
     pub fn new() -> Queue {
         Queue {
             num_edges: 0,
-            queue: PriorityQueue::new()
+            queue: PriorityQueue::new(),
+            lineage: None
         }
     }
+    // Keeps track of the input segments the edges come from,
+    // has to be called before any paths are added
+    pub(crate) fn track_lineage(&mut self) {
+        debug_assert!(self.num_edges == 0, "Lineage tracked from the start");
+        self.lineage = Some(Lineage::new());
+    }
+    pub(crate) fn take_lineage(&mut self) -> Option<Lineage> {
+        self.lineage.take()
+    }
     pub fn add_operand<T: Shape>(&mut self, shape: T, operand: Operand) -> Result<(), Error> {
-        for path in shape.paths() {
-            self.insert_path(path, operand)?;
+        self.add_paths(&shape.paths(), operand)
+    }
+    pub fn add_paths(&mut self, paths: &[&Path], operand: Operand) -> Result<(), Error> {
+        for path in paths {
+            self.add_ring(path.points(), operand)?;
         }
         Ok(())
     }
-
-    fn insert_path(&mut self, path: &Path, operand: Operand) -> Result<(), Error> {
-        fn next_point_is_identical(point: &Point, index: usize, points: &Vec<Point>) -> bool {
-            if index + 1 < points.len() {
-                point == &points[index + 1]
-            } else {
-                point == &points[0]
+    // Edges are inserted as the points arrive, without the ring
+    // being collected into a path first
+    pub fn add_ring<P: Borrow<Point>, I: IntoIterator<Item = P>>(&mut self, ring: I, operand: Operand) -> Result<(), Error> {
+        self.insert_ring(ring.into_iter().map(|point| Ok(point.borrow().clone())), operand)
+    }
+    // Coordinates are validated one by one, the edges inserted
+    // before the first invalid one are taken out again
+    pub fn add_coordinates<I: IntoIterator<Item = (i32, i32)>>(&mut self, ring: I, operand: Operand) -> Result<(), Error> {
+        self.insert_ring(ring.into_iter().map(|(x, y)| Point::new(x, y)), operand)
+    }
+    // A ring failing part of the way leaves the queue as it was
+    fn insert_ring<I: Iterator<Item = Result<Point, Error>>>(&mut self, ring: I, operand: Operand) -> Result<(), Error> {
+        let num_edges = self.num_edges;
+        let inserted = self.insert_edges(ring, operand);
+        if inserted.is_err() {
+            let queue = std::mem::replace(&mut self.queue, PriorityQueue::new());
+            for edge in queue.into_vec().into_iter().filter(|edge| edge.index < num_edges) {
+                self.push_edge(edge);
             }
-        }
-
-        fn next_point<'path>(
-            last_point: (&Point, usize), points: &'path Vec<Point>
-        ) -> Option<(&'path Point, usize)> {
-            let mut index = last_point.1 + 1;
-            let mut next: Option<&Point> = None;
-            while next.is_none() && index < points.len() {
-                let candidate = &points[index];
-                if candidate != last_point.0 && !next_point_is_identical(candidate, index, points) {
-                    next = Some(candidate);
-                } else {
-                    index += 1;
-                }
+            self.num_edges = num_edges;
+            if let Some(lineage) = self.lineage.as_mut() {
+                lineage.drop_path(operand, num_edges);
             }
-            if let Some(next) = next {
-                Some((next, index))
-            } else if &points[0] != last_point.0 {
-                Some((&points[0], points.len()))
-            } else {
-                None
+        }
+        inserted
+    }
+    fn insert_edges<I: Iterator<Item = Result<Point, Error>>>(&mut self, ring: I, operand: Operand) -> Result<(), Error> {
+        let path = self.lineage.as_mut().map_or(0, |lineage| lineage.next_path(operand, 1));
+        let id = SegmentId::new(operand, path, 0);
+        let mut first: Option<Point> = None;
+        let mut last: Option<(Point, usize)> = None;
+        for (index, point) in ring.enumerate() {
+            let point = point?;
+            match last {
+                None => first = Some(point.clone()),
+                Some((ref previous, _)) if *previous == point => {},
+                // Repeated points skipped, the segment starts at the last of them
+                Some((ref previous, start)) => self.insert_segment(previous, &point, id.at(start))?
             }
+            last = Some((point, index));
         }
-
-        if path.points().len() < 2 { return Ok(()); }
-        let mut last_point = Some((&path.points()[0], 0));
-        while let Some((next_point, next_index)) = next_point(
-            last_point.unwrap(), path.points()
-        ) {
-
-            self.insert_edge(
-                last_point.unwrap().0, next_point, operand
-            ).expect("Unexpected null edge");
-            last_point = Some((next_point, next_index));
+        match (first, last) {
+            (Some(first), Some((last, start))) if first != last => self.insert_segment(&last, &first, id.at(start)),
+            _ => Ok(())
         }
+    }
+    fn insert_segment(&mut self, start: &Point, end: &Point, id: SegmentId) -> Result<(), Error> {
+        let edge = self.create_edge(start, end, id.operand())?;
+        if let Some(lineage) = self.lineage.as_mut() {
+            lineage.record_origin(&edge, id);
+        }
+        self.push_edge(edge);
         Ok(())
     }
     pub fn insert_edge(
@@ -190,4 +234,45 @@ fn priority_queue_test() {
     pq.push(p0.clone(), Priority{x: zero, y: zero});
     pq.push(p1.clone(), Priority{x: zero, y: one});
     assert_eq!(pq.peek().as_ref().unwrap().0, &p0);
-}
\ No newline at end of file
+}
+#[test]
+fn pop_edge_behind_sweep_line_test() {
+    let mut queue = Queue::new();
+    let p0 = Point::new(0, 0).unwrap();
+    let p1 = Point::new(10, 10).unwrap();
+    queue.insert_edge(&p0, &p1, Operand::Subject).unwrap();
+    match queue.pop_edge(Coordinate::new(5)) {
+        Err(Error::MisplacedEdgeError { edge, point, x }) => {
+            assert_eq!(edge, 0);
+            assert_eq!(point, p0);
+            assert_eq!(x, Coordinate::new(5));
+        },
+        _ => panic!("Misplaced edge error expected")
+    }
+    assert!(queue.pop_edge(Coordinate::new(0)).unwrap().is_some());
+}
+#[test]
+fn add_ring_test() {
+    let points: Vec<Point> = vec![(0, 0), (0, 0), (10, 0), (10, 10), (10, 10), (0, 0)].into_iter()
+        .map(|(x, y)| Point::new(x, y).unwrap())
+        .collect();
+    let mut queue = Queue::new();
+    queue.add_ring(&points, Operand::Subject).unwrap();
+    assert_eq!(queue.num_edges(), 3);
+    queue.add_ring(points.into_iter().take(1), Operand::Clipping).unwrap();
+    assert_eq!(queue.num_edges(), 3);
+    queue.add_coordinates(vec![(0, 0), (5, 5), (0, 5)], Operand::Clipping).unwrap();
+    assert_eq!(queue.num_edges(), 6);
+    let invalid = queue.add_coordinates(vec![(0, 0), (5, 5), (Coordinate::MAX + 1, 0)], Operand::Clipping);
+    assert!(matches!(invalid, Err(Error::OutOfBoundsError(_))));
+    // Nothing of the invalid ring is left
+    assert_eq!(queue.num_edges(), 6);
+    let mut indices: Vec<usize> = Vec::new();
+    while let Some(x) = queue.next_x() {
+        while let Some(edge) = queue.pop_edge(x).unwrap() {
+            indices.push(edge.index);
+        }
+    }
+    indices.sort_unstable();
+    assert_eq!(indices, (0..6).collect::<Vec<usize>>());
+}
@@ -3,6 +3,8 @@ use std::hash::{Hash};
 use std::collections::HashMap;
 use std::fmt::{Debug, Display};
 use std::borrow::Borrow;
+use crate::primitives::{AbstractPoint, Point};
+use crate::units::Coordinate;
 pub trait ValidationError {
     fn new() -> Self where Self: Sized;
     fn num_errors(&self) -> usize;
@@ -111,7 +113,11 @@ pub enum Error {
     TooManyEdgesError,
     NullEdgeError,
     FatalError(String),
-    TooManyPathsError
+    TooManyPathsError,
+    UnmatchedChainError { point: Point, x: Coordinate },
+    MisplacedEdgeError { edge: usize, point: Point, x: Coordinate },
+    NotSimpleError { point: Point },
+    SinkError(String)
 }
 impl Error {
     pub fn out_of_bounds(
@@ -140,7 +146,7 @@ impl Error {
                 .map(|(concern, _)| format!("{}", concern))
                 .collect();
             let string = format!("{}", parameters.join(", "));
-            Some(Error::OutOfBoundsError(string))
+            Some(Error::NotANumberError(string))
         }
     }
 }
@@ -164,6 +170,18 @@ impl Display for Error{
             },
             Error::FatalError(string) => {
                 write!(f, "Fatal error: {}", string)
+            },
+            Error::UnmatchedChainError { point, x } => {
+                write!(f, "Unmatched chain at {}, sweep line at {}", point.inspect(), x)
+            },
+            Error::MisplacedEdgeError { edge, point, x } => {
+                write!(f, "Edge {} at {} misplaced, sweep line at {}", edge, point.inspect(), x)
+            },
+            Error::NotSimpleError { point } => {
+                write!(f, "Result not simple at {}", point.inspect())
+            },
+            Error::SinkError(string) => {
+                write!(f, "Sink failed: {}", string)
             }
         }
     }
@@ -189,8 +207,19 @@ impl std::error::Error for Error{
             },
             Error::FatalError(_) => {
                 "Fatal error"
+            },
+            Error::UnmatchedChainError { .. } => {
+                "Unmatched chain"
+            },
+            Error::MisplacedEdgeError { .. } => {
+                "Misplaced edge"
+            },
+            Error::NotSimpleError { .. } => {
+                "Result not simple"
+            },
+            Error::SinkError(_) => {
+                "Sink failed"
             }
-
         }
     }
 }
@@ -201,7 +230,8 @@ mod test {
         ValidationError,
         ValidationErrors,
         BasicValidationError,
-        Validation
+        Validation,
+        Error
     };
 
     #[test]
@@ -239,4 +269,15 @@ mod test {
         assert_eq!(val1.merge(&val0), 1);
         assert_eq!(val1.num_errors(), 3);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn not_a_number_test() {
+        let mut validation: Validation<&'static str, BasicValidationError> = Validation::new();
+        assert!(Error::not_a_number(&validation).is_none());
+        validation.report_error(&"x", &BasicValidationError::new());
+        match Error::not_a_number(&validation) {
+            Some(Error::NotANumberError(parameters)) => assert_eq!(parameters, "x"),
+            other => panic!("Expected NaN parameters, got {:?}", other)
+        }
+    }
+}
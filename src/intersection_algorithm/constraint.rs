@@ -144,14 +144,14 @@ mod test {
 
     #[test]
     fn merge_test() {
-        let a = Constraint{min: Pseudoangle::new(0.2), max: Pseudoangle::new(0.6)};
-        let b = Constraint{min: Pseudoangle::new(0.4), max: Pseudoangle::new(0.8)};
+        let a = Constraint{min: Pseudoangle::new(0.2).unwrap(), max: Pseudoangle::new(0.6).unwrap()};
+        let b = Constraint{min: Pseudoangle::new(0.4).unwrap(), max: Pseudoangle::new(0.8).unwrap()};
         let m = Constraint::merge(&a, &b);
-        assert_eq!(m.min, Pseudoangle::new(0.4));
-        assert_eq!(m.max, Pseudoangle::new(0.6));
+        assert_eq!(m.min, Pseudoangle::new(0.4).unwrap());
+        assert_eq!(m.max, Pseudoangle::new(0.6).unwrap());
         let m = Constraint::merge(&b, &a);
-        assert_eq!(m.min, Pseudoangle::new(0.4));
-        assert_eq!(m.max, Pseudoangle::new(0.6));
+        assert_eq!(m.min, Pseudoangle::new(0.4).unwrap());
+        assert_eq!(m.max, Pseudoangle::new(0.6).unwrap());
     }
     #[test]
     fn y_at_pseudoangle_test() {
@@ -333,7 +333,7 @@ mod test {
         assert!(c_ttfu.is_too_tight(&e0.straight, &e0.pseudoangle_for_upper_left(), &coo));
         let cl= Vector::from(&Point::new(4, -2).unwrap());
         let a_cl = cl.pseudoangle().unwrap();
-        assert_eq!(a_cl, Pseudoangle::new(0.6666666666666666));
+        assert_eq!(a_cl, Pseudoangle::new(0.6666666666666666).unwrap());
         let c_ttfl = c.constrain_maximum(&a_cl);
         assert_eq!(c_ttfl.min, a1);
         assert_eq!(c_ttfl.max, a_cl);
@@ -7,19 +7,22 @@ use crate::intersection_algorithm::ray::Ray;
 pub use scope::Scope;
 use crate::intersection_algorithm::scope::Key;
 use crate::intersection_algorithm::position::Position;
+use crate::arrangement::Arrangement;
 
 
 pub struct IntersectionAlgorithm {}
 impl IntersectionAlgorithm {
     pub fn perform<T: Shape>(a: T, b: T) -> Result<Vec<Edge>, Error> {
         let mut queued_edges = Queue::build(a, b)?;
-
+        IntersectionAlgorithm::sweep(&mut queued_edges)
+    }
+    pub fn sweep(queued_edges: &mut Queue) -> Result<Vec<Edge>, Error> {
         let mut left: BTreeMap<Key, Ray> = BTreeMap::new();
         let mut positions: BTreeMap<Float, Position> = BTreeMap::new();
         let mut next: Option<Coordinate> = queued_edges.next_x();
         let mut vec: Vec<Edge> = Vec::new();
         while let Some(x) = next {
-            let scope = Scope::build(left, positions, &x, &mut queued_edges)?;
+            let scope = Scope::build(left, positions, &x, queued_edges)?;
             let edges: Vec<Edge> = scope.left_hand_edges();
             vec.extend(edges);
             let (new_left, new_positions, next_scope) = scope.pass_over();
@@ -35,6 +38,10 @@ impl IntersectionAlgorithm {
         }
         Ok(vec)
     }
+    pub fn arrangement<T: Shape>(a: T, b: T) -> Result<Arrangement, Error> {
+        let edges = IntersectionAlgorithm::perform(a, b)?;
+        Ok(Arrangement::build(&edges))
+    }
 }
 pub mod scope;
 pub mod traverse;
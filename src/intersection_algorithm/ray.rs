@@ -61,7 +61,7 @@ impl Ray {
     ) -> Result<Option<Snippet>, Error> {
         let point = self.endpoint().clone();
         if &point == edge.lower_right() {
-            self.insert_unsafe(edge);
+            self.insert_unsafe(edge, queued_edges);
             Ok(None)
         } else if point.is_lower_right(&edge.lower_right()) {
             let snippet = self.snip_self(
@@ -69,7 +69,7 @@ impl Ray {
                 &Constraint::LOOSE,
                 Some((x, y)), queued_edges
             )?;
-            self.insert_unsafe(edge);
+            self.insert_unsafe(edge, queued_edges);
             Ok(snippet)
         } else {
             let snippet = self.snip_edge(
@@ -81,8 +81,9 @@ impl Ray {
             Ok(Some(snippet))
         }
     }
-    pub fn insert_unsafe(&mut self, new: Edge) {
+    pub fn insert_unsafe(&mut self, new: Edge, queued_edges: &mut dyn AbstractQueue) {
         if let Some(old) = self.edge.take() {
+            queued_edges.record_merge(&old, &new);
             let merged = old.merge(new);
             self.edge = Some(merged);
         } else {
@@ -125,7 +126,7 @@ impl Ray {
                 if left.pseudoangle_for_upper_left() == snippet.original_angle &&
                     left.straight.cross_with_vertical_or_upper(x).unwrap() == y {
                     let (left, snippet) = snippet.take_left();
-                    self.insert_unsafe(left.unwrap().0);
+                    self.insert_unsafe(left.unwrap().0, queued_edges);
                     snippet
                 } else {
                     snippet
@@ -181,12 +182,11 @@ impl Ray {
         (subject, clipping)
     }
     pub fn partition(&self, operand: Operand, previous:(&Partition)) -> &Partition {
-        let proper = match self.count(Some(operand)) %2 {
-            0 => previous.previous_for_even(),
-            1 => previous.previous_for_odd(),
-            _ => panic!("Impossible")
-        };
-        proper
+        if self.count(Some(operand)) % 2 == 1 {
+            previous.previous_for_odd()
+        } else {
+            previous.previous_for_even()
+        }
     }
     pub fn drawable_edges(
         &self,
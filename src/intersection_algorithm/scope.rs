@@ -99,7 +99,7 @@ impl Scope {
     fn pop_batch(at: Coordinate, queued_edges: &mut dyn AbstractQueue) -> Result<(Scope, Stack), Error> {
         let mut scope = Scope::new(at);
         let mut stack = Stack::new();
-        while let Some(edge) = queued_edges.pop_edge(at) {
+        while let Some(edge) = queued_edges.pop_edge(at)? {
             let tuple = scope.insert_edge(
                 edge,
                 Constraint::LOOSE,
@@ -142,7 +142,7 @@ impl Scope {
                 y, queued_edges
             ).unwrap();
             let support = self.get_support(&y).unwrap();
-            support.insert_to_left(edge, &mut self.lhs);
+            support.insert_to_left(edge, queued_edges, &mut self.lhs);
             Ok(snippets)
         } else {
             let (snippets, _) = self.insert_traverse(edge, constraint, queued_edges)?;
@@ -812,7 +812,11 @@ impl BentleyOttmann for Scope {
         ) = if edge.upper_left().x() < self.x() {
             let end = edge.lower_right();
             if end.x() < self.x() {
-                panic!("Edge to the left of the sweep line");
+                return Err(Error::MisplacedEdgeError {
+                    edge: edge.index,
+                    point: end.clone(),
+                    x: self.x()
+                });
             } else if end.x() == self.x() {
                 let (mut snippets, removed) = self.safe_create_support(end.y(), queued_edges)?;
                 let support = self.get_support(&end.y()).unwrap();
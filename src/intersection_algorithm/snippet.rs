@@ -59,6 +59,7 @@ impl Snippet {
         let original_angle = edge.pseudoangle_for_upper_left();
         let left_index = edge.index;
         let right_index = queued_edges.next_edge_index()?;
+        queued_edges.record_split(&edge, right_index, point);
         let (left, right) = edge.left_split(point, left_index, right_index);
         let left_option = if let Some(left) = left {
             Some((left, *constraint))
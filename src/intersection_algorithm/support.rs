@@ -109,10 +109,14 @@ impl Support {
                 let snippet= self.insert_traverse(edge, queued_edges, lhs)?;
                 Ok((snippet, false, false, false))
             } else if end.x() == self.point.x() {
-                self.insert_to_left(edge, lhs);
+                self.insert_to_left(edge, queued_edges, lhs);
                 Ok((None, false, false, false))
             } else {
-                panic!("Edge end is to the left of the sweep line");
+                Err(Error::MisplacedEdgeError {
+                    edge: edge.index,
+                    point: end.clone(),
+                    x: self.point.x()
+                })
             }
         } else if start.x() == self.point.x() {
             if end.x() == self.point.x() {
@@ -121,14 +125,18 @@ impl Support {
                 self.insert_to_right(edge, queued_edges, rhs)
             }
         } else {
-            panic!("Edge start is to the right of the sweep line");
+            Err(Error::MisplacedEdgeError {
+                edge: edge.index,
+                point: start.clone(),
+                x: self.point.x()
+            })
         }
     }
     pub fn insert_traverse(&self, edge: Edge, queued_edges: &mut dyn AbstractQueue, lhs: &mut Lhs) -> Result<Option<Snippet>, Error> {
         let point: &Point = self.point();
         let snippet = Snippet::snip(point, edge, &Constraint::LOOSE, queued_edges)?;
         let (left, snippet) = snippet.take_left();
-        self.insert_to_left(left.unwrap().0, lhs);
+        self.insert_to_left(left.unwrap().0, queued_edges, lhs);
         Ok(Some(snippet))
     }
     pub fn insert_ray_to_left(&self, angle: Pseudoangle, ray: Ray, lhs: &mut Lhs) {
@@ -136,13 +144,13 @@ impl Support {
         let key = self.key_at(angle).reversed();
         lhs.insert(key, ray);
     }
-    pub fn insert_to_left(&self, edge: Edge, lhs: &mut Lhs) {
+    pub fn insert_to_left(&self, edge: Edge, queued_edges: &mut dyn AbstractQueue, lhs: &mut Lhs) {
         debug_assert!(edge.upper_left().x() < self.point().x(), "Edge doesn't start left to the position");
         debug_assert!(edge.lower_right().x() == self.point().x(), "Edge doesn't end at the position");
         let angle = edge.pseudoangle_for_pivot(&self.point).unwrap();
         let key = self.key_at(angle).reversed();
         if let Some(ray) = lhs.get_mut(&key) {
-            ray.insert_unsafe(edge);
+            ray.insert_unsafe(edge, queued_edges);
         } else {
             let ray = Ray::new(edge, angle);
             self.insert_ray_to_left(angle, ray, lhs);
@@ -245,12 +253,12 @@ mod test {
         let edge2 = Edge::original(2, Operand::Clipping, &s2, &point).unwrap();
         let edge3 = Edge::original(3, Operand::Clipping, &point, &e3).unwrap();
         let edge4 = Edge::original(4, Operand::Clipping, &s2, &point).unwrap();
-        support.insert_to_left(edge0.clone(), &mut lhs);
-        support.insert_to_left(edge1.clone(), &mut lhs);
-        support.insert_to_left(edge2.clone(), &mut lhs);
         let mut queue = Queue::new();
+        support.insert_to_left(edge0.clone(), &mut queue, &mut lhs);
+        support.insert_to_left(edge1.clone(), &mut queue, &mut lhs);
+        support.insert_to_left(edge2.clone(), &mut queue, &mut lhs);
         let _ = support.insert_to_vertical(edge3.clone(), &mut queue, &mut lhs);
-        support.insert_to_left(edge4.clone(), &mut lhs);
+        support.insert_to_left(edge4.clone(), &mut queue, &mut lhs);
         assert_eq!(support.left_hand_side(&lhs).count(), 3);
     }
     #[test]
@@ -268,7 +276,7 @@ mod test {
         let s0 = Point::new(0, 5).unwrap();
         let e0 = Point::new(10, 5).unwrap();
         let e0 = Edge::original(0, Operand::Clipping, &s0, &e0).unwrap();
-        support.insert_to_left(e0, &mut lhs);
+        support.insert_to_left(e0, &mut gb, &mut lhs);
         assert_eq!(support.in_scope(&rhs), false);
         assert!(support.first_ray(&rhs).is_none());
         assert!(support.last_ray(&rhs).is_none());
@@ -305,13 +313,13 @@ mod test {
         assert_eq!(support.first_ray(&rhs).unwrap().angle, a2);
         assert_eq!(support.last_ray(&rhs).unwrap().angle, a3);
 
-        assert!(support.remove_from_right(&Pseudoangle::new(0.25), &mut rhs).is_none());
+        assert!(support.remove_from_right(&Pseudoangle::new(0.25).unwrap(), &mut rhs).is_none());
         let r0 = support.remove_from_right(&a1, &mut rhs).unwrap();
-        assert_eq!(r0.angle, Pseudoangle::new(1.0));
+        assert_eq!(r0.angle, Pseudoangle::new(1.0).unwrap());
         let r1 = support.remove_from_right(&a2, &mut rhs).unwrap();
-        assert_eq!(r1.angle, Pseudoangle::new(0.5));
+        assert_eq!(r1.angle, Pseudoangle::new(0.5).unwrap());
         let r2 = support.remove_from_right(&a3, &mut rhs).unwrap();
-        assert_eq!(r2.angle, Pseudoangle::new(1.5));
+        assert_eq!(r2.angle, Pseudoangle::new(1.5).unwrap());
 
         assert_eq!(support.in_scope(&rhs), false);
     }
@@ -0,0 +1,118 @@
+use crate::shape::Relation;
+use std::fmt::Display;
+use std::fmt;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Location {
+    Interior,
+    Boundary,
+    Exterior
+}
+impl Location {
+    fn index(&self) -> usize {
+        match self {
+            Location::Interior => 0,
+            Location::Boundary => 1,
+            Location::Exterior => 2
+        }
+    }
+}
+// Dimension of an intersection, Empty standing for F
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Dimension {
+    Empty,
+    Zero,
+    One,
+    Two
+}
+impl Dimension {
+    fn symbol(&self) -> char {
+        match self {
+            Dimension::Empty => 'F',
+            Dimension::Zero => '0',
+            Dimension::One => '1',
+            Dimension::Two => '2'
+        }
+    }
+}
+// DE-9IM matrix, rows for the locations in the subject,
+// columns for the locations in the clipping shape
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct IntersectionMatrix {
+    entries: [[Dimension; 3]; 3]
+}
+impl IntersectionMatrix {
+    pub(crate) fn new(entries: [[Dimension; 3]; 3]) -> IntersectionMatrix {
+        IntersectionMatrix { entries }
+    }
+    pub fn get(&self, subject: Location, clipping: Location) -> Dimension {
+        self.entries[subject.index()][clipping.index()]
+    }
+    // Pattern of nine symbols read row by row: T for any non-empty
+    // intersection, F for an empty one, * for anything, or a dimension
+    pub fn matches(&self, pattern: &str) -> bool {
+        let symbols: Vec<char> = pattern.chars().collect();
+        symbols.len() == 9 && self.entries.iter().flatten().zip(symbols.iter()).all(|(dimension, symbol)| {
+            match symbol {
+                '*' => true,
+                'T' | 't' => *dimension != Dimension::Empty,
+                'F' | 'f' => *dimension == Dimension::Empty,
+                symbol => dimension.symbol() == *symbol
+            }
+        })
+    }
+    pub fn transpose(&self) -> IntersectionMatrix {
+        let mut entries = self.entries;
+        for (row, dimensions) in entries.iter_mut().enumerate() {
+            for (column, dimension) in dimensions.iter_mut().enumerate() {
+                *dimension = self.entries[column][row];
+            }
+        }
+        IntersectionMatrix { entries }
+    }
+    pub fn relation(&self) -> Relation {
+        let interior = |subject, clipping| self.get(subject, clipping) != Dimension::Empty;
+        match (
+            interior(Location::Interior, Location::Interior),
+            interior(Location::Interior, Location::Exterior),
+            interior(Location::Exterior, Location::Interior)
+        ) {
+            (true, false, false) => Relation::Identical,
+            (true, true, false) => Relation::Contains,
+            (true, false, true) => Relation::Contained,
+            (true, true, true) => Relation::PartlyContained,
+            _ if interior(Location::Boundary, Location::Boundary) => Relation::Touching,
+            _ => Relation::Unrelated
+        }
+    }
+}
+impl Display for IntersectionMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbols: String = self.entries.iter().flatten().map(Dimension::symbol).collect();
+        write!(f, "{}", symbols)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{IntersectionMatrix, Dimension, Location};
+    use crate::shape::Relation;
+
+    #[test]
+    fn intersection_matrix_test() {
+        let (f, zero, one, two) = (Dimension::Empty, Dimension::Zero, Dimension::One, Dimension::Two);
+        let touching = IntersectionMatrix::new([[f, f, two], [f, zero, one], [two, one, two]]);
+        assert_eq!(touching.to_string(), "FF2F01212");
+        assert!(touching.matches("FF*F0****"));
+        assert!(touching.matches("ff*fT****"));
+        assert!(!touching.matches("T********"));
+        assert!(!touching.matches("FF2F0121"));
+        assert_eq!(touching.relation(), Relation::Touching);
+        assert_eq!(touching.get(Location::Boundary, Location::Exterior), one);
+
+        let contains = IntersectionMatrix::new([[two, one, two], [f, one, one], [f, f, two]]);
+        assert_eq!(contains.relation(), Relation::Contains);
+        assert_eq!(contains.transpose().to_string(), "2FF11F212");
+        assert_eq!(contains.transpose().relation(), Relation::Contained);
+    }
+}
@@ -0,0 +1,217 @@
+use crate::primitives::{Point, Straight};
+use crate::edge::Edge;
+use crate::operation::Operand;
+use crate::shape::path::coordinates;
+use std::collections::HashMap;
+
+// Segment of an input path, numbered as by Path::segment_at,
+// paths numbered in the order the operand yields them
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct SegmentId {
+    operand: Operand,
+    path: usize,
+    segment: usize
+}
+impl SegmentId {
+    pub(crate) fn new(operand: Operand, path: usize, segment: usize) -> SegmentId {
+        SegmentId { operand, path, segment }
+    }
+    pub(crate) fn at(&self, segment: usize) -> SegmentId {
+        SegmentId { segment, ..*self }
+    }
+    pub fn operand(&self) -> Operand { self.operand }
+    pub fn path(&self) -> usize { self.path }
+    pub fn segment(&self) -> usize { self.segment }
+}
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum IntersectionKind {
+    // Segments cross, the point lying inside all of them
+    Crossing,
+    // The point is an endpoint of some of the segments
+    Touching,
+    // Collinear segments share a stretch starting or ending at the point
+    Overlapping
+}
+#[derive(Clone, Debug)]
+pub struct IntersectionPoint {
+    point: Point,
+    kind: IntersectionKind,
+    segments: Vec<SegmentId>
+}
+impl IntersectionPoint {
+    fn new(point: &Point, segments: &[&Origin], overlapping: bool) -> IntersectionPoint {
+        let kind = if overlapping {
+            IntersectionKind::Overlapping
+        } else if segments.iter().any(|(_, straight)| straight.is_endpoint(point)) {
+            IntersectionKind::Touching
+        } else {
+            IntersectionKind::Crossing
+        };
+        let segments = segments.iter().map(|(id, _)| *id).collect();
+        IntersectionPoint { point: point.clone(), kind, segments }
+    }
+    pub fn point(&self) -> &Point { &self.point }
+    pub fn kind(&self) -> IntersectionKind { self.kind }
+    // Input segments meeting at the point
+    pub fn segments(&self) -> &Vec<SegmentId> { &self.segments }
+}
+// Input segment along with the edge it became, before any splits
+type Origin = (SegmentId, Straight);
+// Tells which input segments the edges of a sweep come from
+// and where they were snipped
+pub(crate) struct Lineage {
+    paths: (usize, usize),
+    origins: HashMap<usize, Origin>,
+    parents: HashMap<usize, usize>,
+    // Edges merged into each edge, those of its parent when split
+    merges: HashMap<usize, Vec<usize>>,
+    splits: Vec<(Point, Vec<usize>)>
+}
+impl Lineage {
+    pub(crate) fn new() -> Lineage {
+        Lineage {
+            paths: (0, 0),
+            origins: HashMap::new(),
+            parents: HashMap::new(),
+            merges: HashMap::new(),
+            splits: Vec::new()
+        }
+    }
+    // Index of the first of the paths being added to the operand
+    pub(crate) fn next_path(&mut self, operand: Operand, count: usize) -> usize {
+        let paths = match operand {
+            Operand::Subject => &mut self.paths.0,
+            Operand::Clipping => &mut self.paths.1
+        };
+        *paths += count;
+        *paths - count
+    }
+    // Forgets the last path added to the operand and the edges
+    // from the given index on, before any were split
+    pub(crate) fn drop_path(&mut self, operand: Operand, edges: usize) {
+        match operand {
+            Operand::Subject => self.paths.0 -= 1,
+            Operand::Clipping => self.paths.1 -= 1
+        }
+        self.origins.retain(|index, _| *index < edges);
+    }
+    pub(crate) fn record_origin(&mut self, edge: &Edge, id: SegmentId) {
+        self.origins.insert(edge.index, (id, edge.straight.clone()));
+    }
+    pub(crate) fn record_split(&mut self, edge: &Edge, right_index: usize, point: &Point) {
+        self.parents.insert(right_index, edge.index);
+        let mut indices = vec![edge.index];
+        if let Some(merged) = self.merges.get(&edge.index) {
+            indices.extend(merged.iter());
+            self.merges.insert(right_index, merged.clone());
+        }
+        self.splits.push((point.clone(), indices));
+    }
+    pub(crate) fn record_merge(&mut self, edge: &Edge, merged: &Edge) {
+        let mut indices = self.merges.remove(&merged.index).unwrap_or_default();
+        indices.push(merged.index);
+        self.merges.entry(edge.index).or_default().append(&mut indices);
+    }
+    fn origin(&self, mut index: usize) -> Option<usize> {
+        while let Some(parent) = self.parents.get(&index) {
+            index = *parent;
+        }
+        if self.origins.contains_key(&index) { Some(index) } else { None }
+    }
+    // Points where segments of both operands meet, either because edges
+    // were snipped there or because the segments end there
+    pub(crate) fn intersection_points(&self) -> Vec<IntersectionPoint> {
+        self.meetings().into_iter().filter_map(|(point, segments)| {
+            let (subject, clipping): (Vec<_>, Vec<_>) = segments.iter().copied()
+                .partition(|origin| origin.0.operand == Operand::Subject);
+            if subject.is_empty() || clipping.is_empty() { return None; }
+            let overlapping = subject.iter().any(|(_, a)| clipping.iter().any(|(_, b)| overlap_ends_at(a, b, point)));
+            Some(IntersectionPoint::new(point, &segments, overlapping))
+        }).collect()
+    }
+    // Points where segments of the paths meet other than where
+    // a path just goes on from one segment to the next
+    pub(crate) fn self_intersections(&self) -> Vec<IntersectionPoint> {
+        self.meetings().into_iter().filter_map(|(point, segments)| {
+            let overlapping = segments.iter().enumerate()
+                .any(|(index, (_, a))| segments[index + 1..].iter().any(|(_, b)| overlap_ends_at(a, b, point)));
+            let continued = segments.len() == 2 &&
+                segments[0].0.path == segments[1].0.path &&
+                segments.iter().all(|(_, straight)| straight.is_endpoint(point));
+            if segments.len() < 2 || (continued && !overlapping) { return None; }
+            Some(IntersectionPoint::new(point, &segments, overlapping))
+        }).collect()
+    }
+    // Input segments at each point where an edge was snipped or a segment ends,
+    // in the order of the points
+    fn meetings(&self) -> Vec<(&Point, Vec<&Origin>)> {
+        let mut points: HashMap<&Point, Vec<usize>> = HashMap::new();
+        for (point, indices) in self.splits.iter() {
+            let origins = indices.iter().filter_map(|index| self.origin(*index));
+            points.entry(point).or_default().extend(origins);
+        }
+        for (index, (_, straight)) in self.origins.iter() {
+            for point in [&straight.start, &straight.end] {
+                points.entry(point).or_default().push(*index);
+            }
+        }
+        let mut meetings: Vec<(&Point, Vec<&Origin>)> = points.into_iter().map(|(point, mut indices)| {
+            // Edges are created in the order of operands, paths and segments
+            indices.sort_unstable();
+            indices.dedup();
+            (point, indices.iter().map(|index| &self.origins[index]).collect())
+        }).collect();
+        meetings.sort_by_key(|(point, _)| coordinates(point));
+        meetings
+    }
+}
+// The stretch shared starts or ends at the point, rather than
+// the point lying inside of it or away from it
+fn overlap_ends_at(a: &Straight, b: &Straight, point: &Point) -> bool {
+    shared_stretch(a, b).is_some_and(|(low, high)| {
+        let along = along(a, coordinates(point));
+        along == low || along == high
+    })
+}
+// Position of the point projected onto the segment, scaled
+// by the squared length of the segment
+fn along(a: &Straight, point: (i64, i64)) -> i64 {
+    let (start, end) = (coordinates(&a.start), coordinates(&a.end));
+    (end.0 - start.0) * (point.0 - start.0) + (end.1 - start.1) * (point.1 - start.1)
+}
+// Range of positions along the first segment shared by both, if they
+// are collinear and share more than a single point
+fn shared_stretch(a: &Straight, b: &Straight) -> Option<(i64, i64)> {
+    let (start, end) = (coordinates(&a.start), coordinates(&a.end));
+    let direction = (end.0 - start.0, end.1 - start.1);
+    let cross = |point: (i64, i64)| direction.0 * (point.1 - start.1) - direction.1 * (point.0 - start.0);
+    let (first, second) = (coordinates(&b.start), coordinates(&b.end));
+    if cross(first) != 0 || cross(second) != 0 { return None; }
+    let (first, second) = (along(a, first), along(a, second));
+    let (low, high) = (first.min(second).max(0), first.max(second).min(along(a, end)));
+    if low < high { Some((low, high)) } else { None }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{shared_stretch, overlap_ends_at};
+    use crate::primitives::{Point, Straight};
+
+    fn straight(start: (i32, i32), end: (i32, i32)) -> Straight {
+        Straight::new(&Point::new(start.0, start.1).unwrap(), &Point::new(end.0, end.1).unwrap())
+    }
+    #[test]
+    fn overlap_test() {
+        assert_eq!(shared_stretch(&straight((0, 0), (10, 10)), &straight((5, 5), (20, 20))), Some((100, 200)));
+        assert_eq!(shared_stretch(&straight((0, 0), (10, 10)), &straight((20, 20), (2, 2))), Some((40, 200)));
+        assert_eq!(shared_stretch(&straight((0, 0), (10, 10)), &straight((10, 10), (20, 20))), None);
+        assert_eq!(shared_stretch(&straight((0, 0), (10, 10)), &straight((5, 6), (20, 21))), None);
+        assert_eq!(shared_stretch(&straight((0, 0), (10, 0)), &straight((5, 0), (5, 10))), None);
+        let point = |x: i32, y: i32| Point::new(x, y).unwrap();
+        let (a, b) = (straight((0, 0), (10, 0)), straight((15, 0), (5, 0)));
+        assert!(overlap_ends_at(&a, &b, &point(5, 0)));
+        assert!(overlap_ends_at(&a, &b, &point(10, 0)));
+        assert!(!overlap_ends_at(&a, &b, &point(7, 0)));
+        assert!(!overlap_ends_at(&a, &b, &point(0, 0)));
+    }
+}
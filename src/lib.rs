@@ -8,6 +8,12 @@ mod operation;
 mod api;
 mod drawing_algorithm;
 mod helpers;
+mod rectangle_clipping;
+mod transform;
+mod intersection_matrix;
+mod arrangement;
+mod intersection_points;
+mod snap_rounding;
 
 #[cfg(test)]
 #[macro_use]
@@ -15,10 +21,15 @@ extern crate lazy_static;
 #[cfg(test)]
 mod test;
 
-pub use api::{Snipper, Solution};
-pub use primitives::{AbstractPoint, Point, Bounds};
-pub use shape::{Shape, Path, Polygon, PathBuilder};
+pub use api::{Snipper, Solution, PathSink};
+pub use primitives::{AbstractPoint, Point, Bounds, Position};
+pub use shape::{Shape, Path, PathDirection, Polygon, PathBuilder, Relation};
 pub use error::Error;
 pub use units::Coordinate;
+pub use transform::Transform;
+pub use operation::{Membership, Operand, Operation};
+pub use intersection_matrix::{IntersectionMatrix, Location, Dimension};
 pub use edge::Queue;
 pub use intersection_algorithm::IntersectionAlgorithm;
+pub use arrangement::{Arrangement, HalfEdge, Face};
+pub use intersection_points::{IntersectionPoint, IntersectionKind, SegmentId};
@@ -14,6 +14,13 @@ impl Display for Operand {
         }
     }
 }
+// Operands a face of the overlay belongs to
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Membership {
+    Subject,
+    Clipping,
+    Both
+}
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum Wrap {
     Inner,
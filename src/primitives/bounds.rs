@@ -68,6 +68,11 @@ impl Bounds{
             true
         }
     }
+    // Unlike collision, bounds sharing just a side or a corner are in contact
+    pub fn have_contact(b1: &Bounds, b2: &Bounds) -> bool {
+        b1.left() <= b2.right() && b2.left() <= b1.right() &&
+            b1.top() <= b2.bottom() && b2.top() <= b1.bottom()
+    }
 }
 impl Sector for Bounds {
     fn contains(&self, point: &Point, mode: &Mode) -> bool {
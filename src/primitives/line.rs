@@ -90,7 +90,7 @@ impl Line {
         }
     }
     pub fn bisection_for_tiebreaker(v1: Vector, v2: Vector) -> (f64, f64) {
-        let one = Pseudoangle::new(1.0);
+        let one = Pseudoangle::ONE;
         let pa1 = *&v1.pseudoangle().unwrap();
         let pa2 = *&v2.pseudoangle().unwrap();
         let (upper, lower) = if pa1 < pa2 {
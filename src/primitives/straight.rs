@@ -97,6 +97,39 @@ impl Straight {
         let v = Vector::new(&self.start, &self.end);
         v.length()
     }
+    // Position of the perpendicular foot along the straight, clamped to its ends
+    fn projection(&self, point: &Point) -> f64 {
+        let relative = Vector::new(&self.start, point);
+        let relative = Point::unchecked(relative.x(), relative.y());
+        match self.vector().perpendicular_projection(&relative) {
+            None => 0f64,
+            Some(t) => t.clamp(0f64, 1f64)
+        }
+    }
+    pub fn closest_point(&self, point: &Point) -> (f64, f64) {
+        let t = self.projection(point);
+        let vector = self.vector();
+        (self.start.float_x() + t * vector.float_x(), self.start.float_y() + t * vector.float_y())
+    }
+    pub fn distance_to_point(&self, point: &Point) -> f64 {
+        let t = self.projection(point);
+        if t == 0f64 {
+            Straight::new(point, &self.start).length()
+        } else if t == 1f64 {
+            Straight::new(point, &self.end).length()
+        } else {
+            let (x, y) = self.closest_point(point);
+            (point.float_x() - x).hypot(point.float_y() - y)
+        }
+    }
+    // Zero when the straights touch or cross
+    pub fn distance(s1: &Straight, s2: &Straight) -> f64 {
+        if Straight::may_cross(s1, s2) { return 0f64; }
+        s1.distance_to_point(&s2.start)
+            .min(s1.distance_to_point(&s2.end))
+            .min(s2.distance_to_point(&s1.start))
+            .min(s2.distance_to_point(&s1.end))
+    }
     pub fn reverse(&self) -> Straight {
         Straight::new(&self.end, &self.start)
     }
@@ -290,3 +323,19 @@ fn straight_test() {
     assert!(!Straight::may_cross(&s9, &s10));
     assert!(Straight::may_cross(&s9, &s11));
 }
+#[test]
+fn distance_test() {
+    let s = Straight::new(&Point::new(0, 0).expect("!"), &Point::new(4, 0).expect("!"));
+    assert_eq!(s.closest_point(&Point::new(2, 3).expect("!")), (2f64, 0f64));
+    assert_eq!(s.closest_point(&Point::new(-2, 3).expect("!")), (0f64, 0f64));
+    assert_eq!(s.distance_to_point(&Point::new(2, -3).expect("!")), 3f64);
+    assert_eq!(s.distance_to_point(&Point::new(7, 4).expect("!")), 5f64);
+    let null = Straight::new(&Point::new(1, 1).expect("!"), &Point::new(1, 1).expect("!"));
+    assert_eq!(null.distance_to_point(&Point::new(4, 5).expect("!")), 5f64);
+    let crossing = Straight::new(&Point::new(2, -1).expect("!"), &Point::new(2, 1).expect("!"));
+    assert_eq!(Straight::distance(&s, &crossing), 0f64);
+    let touching = Straight::new(&Point::new(2, 0).expect("!"), &Point::new(2, 1).expect("!"));
+    assert_eq!(Straight::distance(&s, &touching), 0f64);
+    let apart = Straight::new(&Point::new(6, 1).expect("!"), &Point::new(6, 5).expect("!"));
+    assert_eq!(Straight::distance(&s, &apart), 5f64.sqrt());
+}
@@ -56,13 +56,13 @@ impl Vector {
         let dy = self.float_y();
         let denominator = dx.abs() + dy.abs();
         if denominator == 0.0 {
-            Some(Pseudoangle::new(0.0))
+            Some(Pseudoangle::UP)
         } else {
             let p = dx / denominator;
             if dy < 0.0 {
-                Some(Pseudoangle::new(0.0 + p))
+                Pseudoangle::new(0.0 + p).ok()
             } else {
-                Some(Pseudoangle::new(2.0 - p))
+                Pseudoangle::new(2.0 - p).ok()
             }
         }
     }
@@ -199,65 +199,65 @@ fn pseudoangle_test() {
     let tad3 = 0.5000000149011616;
 
 
-    assert_eq!(va.pseudoangle().unwrap(), Pseudoangle::new(3.0 + tad0));
-    assert_eq!(vb.pseudoangle().unwrap(), Pseudoangle::new(0.0));
-    assert_eq!(vc.pseudoangle().unwrap(), Pseudoangle::new(0.00000005960464122267716));
+    assert_eq!(va.pseudoangle().unwrap(), Pseudoangle::new(3.0 + tad0).unwrap());
+    assert_eq!(vb.pseudoangle().unwrap(), Pseudoangle::new(0.0).unwrap());
+    assert_eq!(vc.pseudoangle().unwrap(), Pseudoangle::new(0.00000005960464122267716).unwrap());
 
     va = Vector::new(&p0, &p2a);
     vb = Vector::new(&p0, &p2);
     vc = Vector::new(&p0, &p2b);
 
-    assert_eq!(va.pseudoangle().unwrap(), Pseudoangle::new(0.49999998509883836));
-    assert_eq!(vb.pseudoangle().unwrap(), Pseudoangle::new(0.5));
-    assert_eq!(vc.pseudoangle().unwrap(), Pseudoangle::new(0.0 + tad3));
+    assert_eq!(va.pseudoangle().unwrap(), Pseudoangle::new(0.49999998509883836).unwrap());
+    assert_eq!(vb.pseudoangle().unwrap(), Pseudoangle::new(0.5).unwrap());
+    assert_eq!(vc.pseudoangle().unwrap(), Pseudoangle::new(0.0 + tad3).unwrap());
 
     va = Vector::new(&p0, &p3a);
     vb = Vector::new(&p0, &p3);
     vc = Vector::new(&p0, &p3b);
 
-    assert_eq!(va.pseudoangle().unwrap(), Pseudoangle::new(0.0 + tad0));
-    assert_eq!(vb.pseudoangle().unwrap(), Pseudoangle::new(1.0));
-    assert_eq!(vc.pseudoangle().unwrap(), Pseudoangle::new(1.000000059604641222676946));
+    assert_eq!(va.pseudoangle().unwrap(), Pseudoangle::new(0.0 + tad0).unwrap());
+    assert_eq!(vb.pseudoangle().unwrap(), Pseudoangle::new(1.0).unwrap());
+    assert_eq!(vc.pseudoangle().unwrap(), Pseudoangle::new(1.000000059604641222676946).unwrap());
 
     va = Vector::new(&p0, &p4a);
     vb = Vector::new(&p0, &p4);
     vc = Vector::new(&p0, &p4b);
 
-    assert_eq!(va.pseudoangle().unwrap(), Pseudoangle::new(1.0 + tad2));
-    assert_eq!(vb.pseudoangle().unwrap(), Pseudoangle::new(1.5));
-    assert_eq!(vc.pseudoangle().unwrap(), Pseudoangle::new(1.0 + tad3));
+    assert_eq!(va.pseudoangle().unwrap(), Pseudoangle::new(1.0 + tad2).unwrap());
+    assert_eq!(vb.pseudoangle().unwrap(), Pseudoangle::new(1.5).unwrap());
+    assert_eq!(vc.pseudoangle().unwrap(), Pseudoangle::new(1.0 + tad3).unwrap());
 
     va = Vector::new(&p0, &p5a);
     vb = Vector::new(&p0, &p5);
     vc = Vector::new(&p0, &p5b);
 
-    assert_eq!(va.pseudoangle().unwrap(), Pseudoangle::new(1.0 + tad0));
-    assert_eq!(vb.pseudoangle().unwrap(), Pseudoangle::new(2.0));
-    assert_eq!(vc.pseudoangle().unwrap(), Pseudoangle::new(2.0 + tad1));
+    assert_eq!(va.pseudoangle().unwrap(), Pseudoangle::new(1.0 + tad0).unwrap());
+    assert_eq!(vb.pseudoangle().unwrap(), Pseudoangle::new(2.0).unwrap());
+    assert_eq!(vc.pseudoangle().unwrap(), Pseudoangle::new(2.0 + tad1).unwrap());
 
     va = Vector::new(&p0, &p6a);
     vb = Vector::new(&p0, &p6);
     vc = Vector::new(&p0, &p6b);
 
-    assert_eq!(va.pseudoangle().unwrap(), Pseudoangle::new(2.0 + tad2));
-    assert_eq!(vb.pseudoangle().unwrap(), Pseudoangle::new(2.5));
-    assert_eq!(vc.pseudoangle().unwrap(), Pseudoangle::new(2.0 + tad3));
+    assert_eq!(va.pseudoangle().unwrap(), Pseudoangle::new(2.0 + tad2).unwrap());
+    assert_eq!(vb.pseudoangle().unwrap(), Pseudoangle::new(2.5).unwrap());
+    assert_eq!(vc.pseudoangle().unwrap(), Pseudoangle::new(2.0 + tad3).unwrap());
 
     va = Vector::new(&p0, &p7a);
     vb = Vector::new(&p0, &p7);
     vc = Vector::new(&p0, &p7b);
 
-    assert_eq!(va.pseudoangle().unwrap(), Pseudoangle::new(2.0 + tad0));
-    assert_eq!(vb.pseudoangle().unwrap(), Pseudoangle::new(3.0));
-    assert_eq!(vc.pseudoangle().unwrap(), Pseudoangle::new(3.0 + tad1));
+    assert_eq!(va.pseudoangle().unwrap(), Pseudoangle::new(2.0 + tad0).unwrap());
+    assert_eq!(vb.pseudoangle().unwrap(), Pseudoangle::new(3.0).unwrap());
+    assert_eq!(vc.pseudoangle().unwrap(), Pseudoangle::new(3.0 + tad1).unwrap());
 
     va = Vector::new(&p0, &p8a);
     vb = Vector::new(&p0, &p8);
     vc = Vector::new(&p0, &p8b);
 
-    assert_eq!(va.pseudoangle().unwrap(), Pseudoangle::new(3.0 + tad2));
-    assert_eq!(vb.pseudoangle().unwrap(), Pseudoangle::new(3.5));
-    assert_eq!(vc.pseudoangle().unwrap(), Pseudoangle::new(3.0 + tad3));
+    assert_eq!(va.pseudoangle().unwrap(), Pseudoangle::new(3.0 + tad2).unwrap());
+    assert_eq!(vb.pseudoangle().unwrap(), Pseudoangle::new(3.5).unwrap());
+    assert_eq!(vc.pseudoangle().unwrap(), Pseudoangle::new(3.0 + tad3).unwrap());
 }
 #[test]
 fn vector_cross_product_test() {
@@ -0,0 +1,436 @@
+use crate::primitives::{AbstractPoint, Point, Bounds, Position};
+use crate::shape::{Path, Polygon};
+use crate::units::Coordinate;
+use crate::Error;
+
+// Boundary is parametrized in the direction of normalized outer paths:
+// top edge from the left, right edge from the top, bottom edge
+// from the right and left edge from the bottom
+struct Rectangle {
+    top: i64,
+    left: i64,
+    bottom: i64,
+    right: i64
+}
+// Entry and exit parameters and directions are kept exact, crossings that
+// round to the same point still have to be linked in the right order
+enum Clipped {
+    Outside,
+    Inside { start: Point, end: Point, entry: Option<f64>, exit: Option<f64>, direction: (i64, i64) }
+}
+struct Piece {
+    points: Vec<Point>,
+    entry: (f64, (i64, i64)),
+    exit: (f64, (i64, i64))
+}
+enum Ring {
+    Inside,
+    Outside,
+    Pieces(Vec<Piece>)
+}
+fn coordinates(point: &Point) -> (i64, i64) {
+    (i64::from(point.x().to_int()), i64::from(point.y().to_int()))
+}
+impl Rectangle {
+    fn new(bounds: &Bounds) -> Result<Rectangle, Error> {
+        let (top, left) = (bounds.top().to_int(), bounds.left().to_int());
+        let (bottom, right) = (bounds.bottom().to_int(), bounds.right().to_int());
+        Point::new(left, top)?;
+        Point::new(right, bottom)?;
+        Ok(Rectangle {
+            top: i64::from(top),
+            left: i64::from(left),
+            bottom: i64::from(bottom),
+            right: i64::from(right)
+        })
+    }
+    fn width(&self) -> i64 {
+        self.right - self.left
+    }
+    fn height(&self) -> i64 {
+        self.bottom - self.top
+    }
+    fn perimeter(&self) -> i64 {
+        2 * (self.width() + self.height())
+    }
+    fn point(x: i64, y: i64) -> Point {
+        Point::unchecked(Coordinate::new(x as i32), Coordinate::new(y as i32))
+    }
+    fn corners(&self) -> Vec<(i64, Point)> {
+        vec![
+            (0, Rectangle::point(self.left, self.top)),
+            (self.width(), Rectangle::point(self.right, self.top)),
+            (self.width() + self.height(), Rectangle::point(self.right, self.bottom)),
+            (2 * self.width() + self.height(), Rectangle::point(self.left, self.bottom))
+        ]
+    }
+    fn path(&self) -> Path {
+        let points = self.corners().into_iter().map(|(_, point)| point).collect();
+        Path::new(&points)
+    }
+    fn parameter(&self, point: &Point) -> f64 {
+        let (x, y) = coordinates(point);
+        let parameter = if y == self.top {
+            x - self.left
+        } else if x == self.right {
+            self.width() + y - self.top
+        } else if y == self.bottom {
+            self.width() + self.height() + self.right - x
+        } else {
+            2 * self.width() + self.height() + self.bottom - y
+        };
+        parameter as f64
+    }
+    fn parameter_on_side(&self, side: usize, x: f64, y: f64) -> f64 {
+        let (width, height) = (self.width() as f64, self.height() as f64);
+        let parameter = match side {
+            0 => 2.0 * width + height + self.bottom as f64 - y,
+            1 => width + y - self.top as f64,
+            2 => x - self.left as f64,
+            _ => width + height + self.right as f64 - x
+        };
+        parameter.rem_euclid(self.perimeter() as f64)
+    }
+    fn is_on_side(&self, a: (i64, i64), b: (i64, i64)) -> bool {
+        (a.0 == b.0 && (a.0 == self.left || a.0 == self.right)) ||
+            (a.1 == b.1 && (a.1 == self.top || a.1 == self.bottom))
+    }
+    // Liang-Barsky, parameters of the part of the segment within
+    // the closed rectangle along with the sides it's cut by
+    fn parameters(&self, a: (i64, i64), b: (i64, i64)) -> Option<((f64, usize), (f64, usize))> {
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let constraints = [
+            (-dx, a.0 - self.left),
+            (dx, self.right - a.0),
+            (-dy, a.1 - self.top),
+            (dy, self.bottom - a.1)
+        ];
+        let mut t0 = (0f64, 0);
+        let mut t1 = (1f64, 0);
+        for (side, (p, q)) in constraints.iter().enumerate() {
+            if *p == 0 {
+                if *q < 0 {
+                    return None;
+                }
+            } else {
+                let r = *q as f64 / *p as f64;
+                if *p < 0 && r > t0.0 {
+                    t0 = (r, side);
+                } else if *p > 0 && r < t1.0 {
+                    t1 = (r, side);
+                }
+            }
+        }
+        if t0.0 < t1.0 {
+            Some((t0, t1))
+        } else {
+            None
+        }
+    }
+    // Crossings are computed with a single division, so that the same
+    // point reached by different segments gets the same parameter
+    fn crossing(&self, a: (i64, i64), b: (i64, i64), side: usize) -> (Point, f64) {
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let (x, y) = match side {
+            0 | 1 => {
+                let x = if side == 0 { self.left } else { self.right };
+                (x as f64, (a.1 * dx + (x - a.0) * dy) as f64 / dx as f64)
+            },
+            _ => {
+                let y = if side == 2 { self.top } else { self.bottom };
+                ((a.0 * dy + (y - a.1) * dx) as f64 / dy as f64, y as f64)
+            }
+        };
+        let point = Rectangle::point(
+            (x.round() as i64).max(self.left).min(self.right),
+            (y.round() as i64).max(self.top).min(self.bottom)
+        );
+        (point, self.parameter_on_side(side, x, y))
+    }
+    fn clip(&self, start: &Point, end: &Point) -> Clipped {
+        let (a, b) = (coordinates(start), coordinates(end));
+        match self.parameters(a, b) {
+            Some(_) if self.is_on_side(a, b) => Clipped::Outside,
+            Some((t0, t1)) => {
+                let (start, entry) = if t0.0 > 0.0 {
+                    let (point, parameter) = self.crossing(a, b, t0.1);
+                    (point, Some(parameter))
+                } else {
+                    (start.clone(), None)
+                };
+                let (end, exit) = if t1.0 < 1.0 {
+                    let (point, parameter) = self.crossing(a, b, t1.1);
+                    (point, Some(parameter))
+                } else {
+                    (end.clone(), None)
+                };
+                let direction = (b.0 - a.0, b.1 - a.1);
+                Clipped::Inside { start, end, entry, exit, direction }
+            },
+            None => Clipped::Outside
+        }
+    }
+    fn split(&self, path: &Path) -> Ring {
+        let points = path.points();
+        let count = points.len();
+        let clipped: Vec<Clipped> = (0..count).map(|index| {
+            self.clip(&points[index], &points[(index + 1) % count])
+        }).collect();
+        let continues = |index: usize| {
+            match &clipped[(index + count - 1) % count] {
+                Clipped::Inside { exit, .. } => exit.is_none(),
+                Clipped::Outside => false
+            }
+        };
+        let first = (0..count).find(|index| {
+            match &clipped[*index] {
+                Clipped::Inside { entry, .. } => entry.is_some() || !continues(*index),
+                Clipped::Outside => false
+            }
+        });
+        let first = match first {
+            Some(first) => first,
+            None => {
+                let inside = clipped.iter().any(|clipped| matches!(clipped, Clipped::Inside { .. }));
+                return if inside { Ring::Inside } else { Ring::Outside };
+            }
+        };
+        let mut pieces: Vec<Piece> = Vec::new();
+        let mut piece: Option<Piece> = None;
+        let finish = |piece: &mut Option<Piece>, pieces: &mut Vec<Piece>| {
+            if let Some(finished) = piece.take() {
+                pieces.push(finished);
+            }
+        };
+        for offset in 0..count {
+            match &clipped[(first + offset) % count] {
+                Clipped::Inside { start, end, entry, exit, direction } => {
+                    if entry.is_some() || piece.is_none() {
+                        finish(&mut piece, &mut pieces);
+                        let entry = entry.unwrap_or_else(|| self.parameter(start));
+                        piece = Some(Piece {
+                            points: vec![start.clone()],
+                            entry: (entry, *direction),
+                            exit: (entry, *direction)
+                        });
+                    }
+                    if let Some(current) = piece.as_mut() {
+                        if current.points.last() != Some(end) {
+                            current.points.push(end.clone());
+                        }
+                        let exit = exit.unwrap_or_else(|| self.parameter(end));
+                        current.exit = (exit, (-direction.0, -direction.1));
+                    }
+                    if exit.is_some() {
+                        finish(&mut piece, &mut pieces);
+                    }
+                },
+                Clipped::Outside => finish(&mut piece, &mut pieces)
+            }
+        }
+        finish(&mut piece, &mut pieces);
+        Ring::Pieces(pieces)
+    }
+    // Pieces meeting at the same point of the boundary are ordered by
+    // the angle they make with the boundary, as if they were infinitesimally
+    // apart: the closer to the forward direction, the later they come
+    fn key(&self, (parameter, ray): (f64, (i64, i64))) -> (f64, f64) {
+        let (width, height) = (self.width() as f64, self.height() as f64);
+        let forward = if parameter < width {
+            (1.0, 0.0)
+        } else if parameter < width + height {
+            (0.0, 1.0)
+        } else if parameter < 2.0 * width + height {
+            (-1.0, 0.0)
+        } else {
+            (0.0, -1.0)
+        };
+        let ray = (ray.0 as f64, ray.1 as f64);
+        let cross = forward.0 * ray.1 - forward.1 * ray.0;
+        let dot = forward.0 * ray.0 + forward.1 * ray.1;
+        (parameter, std::f64::consts::PI - cross.abs().atan2(dot))
+    }
+    // Parity of the point just inside the top left corner, ray cast leftwards
+    // along y = top + e² for infinitesimal e, the point itself lies at x = left + e
+    fn covers_corner(&self, path: &Path) -> bool {
+        let points = path.points();
+        let count = points.len();
+        (0..count).filter(|index| {
+            let (start, end) = (coordinates(&points[*index]), coordinates(&points[(index + 1) % count]));
+            let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+            start.1.min(end.1) <= self.top && self.top < start.1.max(end.1) &&
+                ((start.0 - self.left) * dy + (self.top - start.1) * dx) * dy.signum() <= 0
+        }).count() % 2 == 1
+    }
+    fn corners_between(&self, from: f64, to: f64) -> Vec<Point> {
+        let perimeter = self.perimeter() as f64;
+        let mut passed: Vec<(f64, Point)> = self.corners().into_iter().filter_map(|(parameter, corner)| {
+            let parameter = parameter as f64;
+            let parameter = if parameter > from { parameter } else { parameter + perimeter };
+            if parameter < to { Some((parameter, corner)) } else { None }
+        }).collect();
+        passed.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        passed.into_iter().map(|(_, corner)| corner).collect()
+    }
+    // Piece ends are sorted along the boundary and the stretches between them
+    // are alternately inside and outside the polygon. Pieces are linked through
+    // the stretches inside regardless of the orientation of the paths they come from
+    fn link(&self, pieces: Vec<Piece>, corner: bool) -> Vec<Path> {
+        let perimeter = self.perimeter() as f64;
+        let mut ends: Vec<((f64, f64), usize, bool)> = pieces.iter().enumerate().flat_map(|(index, piece)| {
+            vec![(self.key(piece.entry), index, true), (self.key(piece.exit), index, false)]
+        }).collect();
+        ends.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        let count = ends.len();
+        let mut positions = vec![(0, 0); pieces.len()];
+        for (position, (_, index, is_entry)) in ends.iter().enumerate() {
+            if *is_entry {
+                positions[*index].0 = position;
+            } else {
+                positions[*index].1 = position;
+            }
+        }
+        let before_corner = ends.iter().filter(|((parameter, _), _, _)| *parameter <= 0.0).count();
+        let base = (before_corner + count - 1) % count;
+        let inside_after = |position: usize| corner != ((position + count - base) % 2 == 1);
+        let parameter = |position: usize| (ends[position].0).0;
+        let mut visited = vec![false; pieces.len()];
+        let mut paths = Vec::new();
+        for first in 0..pieces.len() {
+            if visited[first] { continue; }
+            let mut points: Vec<Point> = Vec::new();
+            let push = |points: &mut Vec<Point>, point: &Point| {
+                if points.last() != Some(point) {
+                    points.push(point.clone());
+                }
+            };
+            let start = positions[first].0;
+            let mut position = start;
+            loop {
+                let (_, current, is_entry) = ends[position];
+                visited[current] = true;
+                let other = if is_entry {
+                    for point in pieces[current].points.iter() { push(&mut points, point); }
+                    positions[current].1
+                } else {
+                    for point in pieces[current].points.iter().rev() { push(&mut points, point); }
+                    positions[current].0
+                };
+                let next = if inside_after(other) {
+                    let next = (other + 1) % count;
+                    let to = parameter(next) + if next == 0 { perimeter } else { 0.0 };
+                    for corner in self.corners_between(parameter(other), to) { push(&mut points, &corner); }
+                    next
+                } else {
+                    let next = (other + count - 1) % count;
+                    let to = parameter(other) + if other == 0 { perimeter } else { 0.0 };
+                    for corner in self.corners_between(parameter(next), to).iter().rev() { push(&mut points, corner); }
+                    next
+                };
+                if next == start || visited[ends[next].1] { break; }
+                position = next;
+            }
+            if points.len() > 1 && points.first() == points.last() {
+                points.pop();
+            }
+            paths.push(Path::new(&points));
+        }
+        paths
+    }
+}
+pub fn clip(polygon: &Polygon, bounds: &Bounds) -> Result<Vec<Path>, Error> {
+    let rectangle = Rectangle::new(bounds)?;
+    if rectangle.width() == 0 || rectangle.height() == 0 {
+        return Ok(Vec::new());
+    }
+    let mut paths: Vec<Path> = Vec::new();
+    let mut pieces: Vec<Piece> = Vec::new();
+    let mut covered = false;
+    let mut corner = false;
+    let center = (rectangle.left + rectangle.right, rectangle.top + rectangle.bottom);
+    for path in polygon.paths() {
+        let collision = match path.bounds() {
+            Some(path_bounds) => Bounds::have_collision(path_bounds, bounds),
+            None => false
+        };
+        if !collision { continue; }
+        corner = corner != rectangle.covers_corner(path);
+        match rectangle.split(path) {
+            Ring::Inside => paths.push(path.clone()),
+            Ring::Outside => {
+                if path.position_doubled(center.0, center.1) == Position::In {
+                    covered = !covered;
+                }
+            },
+            Ring::Pieces(mut ring_pieces) => pieces.append(&mut ring_pieces)
+        }
+    }
+    if pieces.is_empty() {
+        if covered {
+            paths.push(rectangle.path());
+        }
+    } else {
+        paths.append(&mut rectangle.link(pieces, corner));
+    }
+    paths.retain(|path| path.area() > 0f64);
+    Ok(paths)
+}
+#[cfg(test)]
+fn square(left: i32, top: i32, right: i32, bottom: i32) -> Path {
+    Path::new(&vec![
+        Point::new(left, top).expect("!"),
+        Point::new(right, top).expect("!"),
+        Point::new(right, bottom).expect("!"),
+        Point::new(left, bottom).expect("!")
+    ])
+}
+#[cfg(test)]
+fn area(paths: &Vec<Path>) -> f64 {
+    crate::Snipper::normalize(paths.clone()).unwrap().polygon().unwrap().area()
+}
+#[test]
+fn clip_hole_test() {
+    let polygon = crate::Snipper::normalize(vec![
+        square(0, 0, 20, 20),
+        square(8, 8, 12, 12)
+    ]).unwrap().polygon().unwrap();
+    let paths = clip(&polygon, &Bounds::new(0, 0, 10, 10)).unwrap();
+    assert_eq!(paths.len(), 1);
+    assert_eq!(area(&paths), 96.0);
+    let paths = clip(&polygon, &Bounds::new(2, 2, 6, 6)).unwrap();
+    assert_eq!(paths.len(), 1);
+    assert_eq!(area(&paths), 16.0);
+    let paths = clip(&polygon, &Bounds::new(9, 9, 11, 11)).unwrap();
+    assert!(paths.is_empty());
+    let paths = clip(&polygon, &Bounds::new(-5, -5, 25, 25)).unwrap();
+    assert_eq!(paths.len(), 2);
+    assert_eq!(area(&paths), 384.0);
+    let paths = clip(&polygon, &Bounds::new(30, 30, 40, 40)).unwrap();
+    assert!(paths.is_empty());
+}
+#[test]
+fn clip_split_test() {
+    let points: Vec<Point> = vec![(0, 0), (30, 0), (30, 30), (20, 30), (20, 10), (10, 10), (10, 30), (0, 30)]
+        .into_iter()
+        .map(|(x, y)| Point::new(x, y).expect("!"))
+        .collect();
+    let polygon = crate::Snipper::normalize(vec![Path::new(&points)]).unwrap().polygon().unwrap();
+    let paths = clip(&polygon, &Bounds::new(20, 0, 40, 30)).unwrap();
+    assert_eq!(paths.len(), 2);
+    assert_eq!(area(&paths), 200.0);
+    let paths = clip(&polygon, &Bounds::new(10, 10, 30, 20)).unwrap();
+    assert!(paths.is_empty());
+    let paths = clip(&polygon, &Bounds::new(0, 0, 40, 40)).unwrap();
+    assert_eq!(paths.len(), 1);
+    assert_eq!(area(&paths), 700.0);
+}
+#[test]
+fn clip_pinched_test() {
+    let points: Vec<Point> = vec![(0, 0), (10, 0), (10, 10), (0, 10), (0, 0), (-10, 0), (-10, -10), (0, -10)]
+        .into_iter()
+        .map(|(x, y)| Point::new(x, y).expect("!"))
+        .collect();
+    let polygon = crate::Snipper::normalize(vec![Path::new(&points)]).unwrap().polygon().unwrap();
+    let paths = clip(&polygon, &Bounds::new(-5, -5, 5, 5)).unwrap();
+    assert_eq!(area(&paths), 50.0);
+}
@@ -0,0 +1,200 @@
+use crate::primitives::{AbstractPoint, Point};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+// Second moments about the centroid by the parallel axis theorem
+pub fn centroidal_moments(area: f64, first: (f64, f64), second: (f64, f64, f64)) -> (f64, f64, f64) {
+    if area == 0f64 { return (0f64, 0f64, 0f64); }
+    let (mx, my) = first;
+    let (xx, yy, xy) = second;
+    (xx - my * my / area, yy - mx * mx / area, xy - mx * my / area)
+}
+// Monotone chain, points on the hull edges are left out
+pub fn convex_hull<'a, I: Iterator<Item = &'a Point>>(points: I) -> Vec<Point> {
+    let mut points: Vec<(i64, i64)> = points
+        .map(|point| (i64::from(point.x().to_int()), i64::from(point.y().to_int())))
+        .collect();
+    points.sort();
+    points.dedup();
+    if points.len() < 3 {
+        return points.into_iter().map(|(x, y)| Point::new(x as i32, y as i32).unwrap()).collect();
+    }
+    let cross = |o: (i64, i64), a: (i64, i64), b: (i64, i64)| {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+    let mut hull: Vec<(i64, i64)> = Vec::with_capacity(points.len() + 1);
+    for pass in 0..2 {
+        let start = hull.len();
+        let chain = |point: (i64, i64), hull: &mut Vec<(i64, i64)>| {
+            while hull.len() >= start + 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], point) <= 0 {
+                hull.pop();
+            }
+            hull.push(point);
+        };
+        if pass == 0 {
+            points.iter().for_each(|point| chain(*point, &mut hull));
+        } else {
+            points.iter().rev().for_each(|point| chain(*point, &mut hull));
+        }
+        hull.pop();
+    }
+    hull.into_iter().map(|(x, y)| Point::new(x as i32, y as i32).unwrap()).collect()
+}
+// Smallest circle enclosing all the points, found incrementally. Only the
+// vertices of their hull matter, taken in random order the expected time is
+// linear, in the order of a path it would be quadratic
+pub fn bounding_circle_radius<'a, I: Iterator<Item = &'a Point>>(points: I) -> f64 {
+    let mut points: Vec<(f64, f64)> = convex_hull(points).iter()
+        .map(|point| (point.float_x(), point.float_y()))
+        .collect();
+    points.shuffle(&mut StdRng::seed_from_u64(0));
+    if points.is_empty() { return 0f64; }
+    let distance = |a: (f64, f64), b: (f64, f64)| (a.0 - b.0).hypot(a.1 - b.1);
+    let encloses = |center: (f64, f64), radius: f64, point: (f64, f64)| {
+        distance(center, point) <= radius * (1.0 + 1e-12) + 1e-9
+    };
+    let diametral = |a: (f64, f64), b: (f64, f64)| {
+        (((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0), distance(a, b) / 2.0)
+    };
+    let circumscribed = |a: (f64, f64), b: (f64, f64), c: (f64, f64)| {
+        let (bx, by, cx, cy) = (b.0 - a.0, b.1 - a.1, c.0 - a.0, c.1 - a.1);
+        let d = 2.0 * (bx * cy - by * cx);
+        if d == 0f64 {
+            // Collinear, the two farthest points span the circle
+            let candidates = [diametral(a, b), diametral(a, c), diametral(b, c)];
+            return candidates.iter().cloned().fold(candidates[0], |best, next| {
+                if next.1 > best.1 { next } else { best }
+            });
+        }
+        let (b2, c2) = (bx * bx + by * by, cx * cx + cy * cy);
+        let x = (cy * b2 - by * c2) / d;
+        let y = (bx * c2 - cx * b2) / d;
+        ((a.0 + x, a.1 + y), x.hypot(y))
+    };
+    let (mut center, mut radius) = (points[0], 0f64);
+    for i in 1..points.len() {
+        if encloses(center, radius, points[i]) { continue; }
+        center = points[i];
+        radius = 0f64;
+        for j in 0..i {
+            if encloses(center, radius, points[j]) { continue; }
+            let circle = diametral(points[i], points[j]);
+            center = circle.0;
+            radius = circle.1;
+            for k in 0..j {
+                if encloses(center, radius, points[k]) { continue; }
+                let circle = circumscribed(points[i], points[j], points[k]);
+                center = circle.0;
+                radius = circle.1;
+            }
+        }
+    }
+    radius
+}
+// Inward offset test: whether some point lies inside an odd number of the rings
+// and at least the distance away from all of their segments. Cells covering the
+// rings are split until they can't hold such a point or are too small to matter,
+// so points reaching less than the precision further may be missed
+pub fn reaches_inward(rings: &[Vec<(f64, f64)>], distance: f64) -> bool {
+    let points = rings.iter().flatten();
+    let (mut low, mut high) = ((f64::INFINITY, f64::INFINITY), (f64::NEG_INFINITY, f64::NEG_INFINITY));
+    for point in points {
+        low = (low.0.min(point.0), low.1.min(point.1));
+        high = (high.0.max(point.0), high.1.max(point.1));
+    }
+    let size = (high.0 - low.0).min(high.1 - low.1);
+    if size <= 0f64 { return false; }
+    if distance <= 0f64 { return true; }
+    let segments: Vec<((f64, f64), (f64, f64))> = rings.iter()
+        .flat_map(|ring| (0..ring.len()).map(move |index| (ring[index], ring[(index + 1) % ring.len()])))
+        .collect();
+    let signed_distance = |point: (f64, f64)| {
+        let mut inside = false;
+        let mut nearest = f64::INFINITY;
+        for (a, b) in segments.iter() {
+            if (a.1 > point.1) != (b.1 > point.1) && point.0 < a.0 + (point.1 - a.1) * (b.0 - a.0) / (b.1 - a.1) {
+                inside = !inside;
+            }
+            let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+            let length = dx * dx + dy * dy;
+            let t = if length == 0f64 { 0f64 } else { (((point.0 - a.0) * dx + (point.1 - a.1) * dy) / length).clamp(0f64, 1f64) };
+            nearest = nearest.min((point.0 - a.0 - t * dx).hypot(point.1 - a.1 - t * dy));
+        }
+        if inside { nearest } else { -nearest }
+    };
+    // Relative to the distance, but coarse enough for the number of cells
+    // along a long and thin shape to stay bounded
+    let extent = (high.0 - low.0).max(high.1 - low.1);
+    let precision = (distance / 1024.0).max(extent / 65536.0);
+    let mut cells: Vec<((f64, f64), f64)> = Vec::new();
+    let mut x = low.0;
+    while x < high.0 {
+        let mut y = low.1;
+        while y < high.1 {
+            cells.push(((x + size / 2.0, y + size / 2.0), size / 2.0));
+            y += size;
+        }
+        x += size;
+    }
+    while let Some((centre, half)) = cells.pop() {
+        let reached = signed_distance(centre);
+        if reached >= distance { return true; }
+        let reach = half * std::f64::consts::SQRT_2;
+        if reached + reach < distance || reach < precision { continue; }
+        let quarter = half / 2.0;
+        for (dx, dy) in [(-1f64, -1f64), (1f64, -1f64), (-1f64, 1f64), (1f64, 1f64)] {
+            cells.push(((centre.0 + dx * quarter, centre.1 + dy * quarter), quarter));
+        }
+    }
+    false
+}
+
+#[test]
+fn convex_hull_test() {
+    let points: Vec<Point> = vec![(0, 0), (2, 1), (4, 0), (4, 4), (2, 4), (0, 4), (1, 2)].into_iter()
+        .map(|(x, y)| Point::new(x, y).unwrap())
+        .collect();
+    let hull = convex_hull(points.iter());
+    let expected: Vec<Point> = vec![(0, 0), (4, 0), (4, 4), (0, 4)].into_iter()
+        .map(|(x, y)| Point::new(x, y).unwrap())
+        .collect();
+    assert!(hull == expected);
+}
+#[test]
+fn bounding_circle_radius_test() {
+    let points: Vec<Point> = vec![(0, 0), (4, 0), (4, 2), (0, 2), (2, 1)].into_iter()
+        .map(|(x, y)| Point::new(x, y).unwrap())
+        .collect();
+    assert!((bounding_circle_radius(points.iter()) - 5f64.sqrt()).abs() < 1e-9);
+    let triangle: Vec<Point> = vec![(0, 0), (6, 0), (3, 1)].into_iter()
+        .map(|(x, y)| Point::new(x, y).unwrap())
+        .collect();
+    assert!((bounding_circle_radius(triangle.iter()) - 3.0).abs() < 1e-9);
+}
+#[test]
+fn bounding_circle_radius_ordered_test() {
+    // All of them on the hull, in order
+    let points: Vec<Point> = (-4095..4096).map(|x: i32| Point::new(x, x * x).unwrap()).collect();
+    let radius = (1f64 + 4095f64 * 4095f64) / 2.0;
+    assert!((bounding_circle_radius(points.iter()) - radius).abs() < 1e-6 * radius);
+}
+#[test]
+fn reaches_inward_test() {
+    let frame = vec![
+        vec![(0f64, 0f64), (10f64, 0f64), (10f64, 10f64), (0f64, 10f64)],
+        vec![(2f64, 2f64), (2f64, 8f64), (8f64, 8f64), (8f64, 2f64)]
+    ];
+    assert!(reaches_inward(&frame, 0.9));
+    // Widest at the corners
+    assert!(reaches_inward(&frame, 1.15));
+    assert!(!reaches_inward(&frame, 1.2));
+    assert!(reaches_inward(&frame[0..1], 4.9));
+    assert!(!reaches_inward(&frame[0..1], 5.1));
+    let sliver = vec![vec![(0f64, 0f64), (100f64, 1f64), (0f64, 2f64)]];
+    assert!(!reaches_inward(&sliver, 1.0));
+    // Long and thin, barely too narrow all along
+    let strip = vec![vec![(0f64, 0f64), (100000f64, 0f64), (100000f64, 2f64), (0f64, 2f64)]];
+    assert!(reaches_inward(&strip, 0.99));
+    assert!(!reaches_inward(&strip, 1.0005));
+}
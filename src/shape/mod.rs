@@ -2,6 +2,7 @@ pub mod path;
 pub mod polygon;
 pub mod shape;
 mod triangular_matrix;
+mod metrics;
 pub use path::{Path, PathDirection, PathBuilder};
-pub use polygon::Polygon;
+pub use polygon::{Polygon, Relation};
 pub use shape::Shape;
\ No newline at end of file
@@ -1,7 +1,13 @@
-use crate::primitives::{AbstractPoint, Point, Position, Mode, Bounds, Straight, Sector, Line, Vector};
+use crate::primitives::{AbstractPoint, Point, Position, Mode, Bounds, Straight, Sector};
 use crate::helpers::approx_eq;
 use crate::shape::{Shape};
+use super::metrics;
+use crate::transform::Transform;
+use crate::Error;
 use crate::units::Coordinate;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::collections::HashSet;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum PathDirection {
@@ -15,6 +21,7 @@ struct PathCount {
     bounds: Option<Bounds>,
     running_area: f64,
     running_centroid: Option<(f64, f64)>,
+    running_second_moment: (f64, f64, f64),
     perimeter: f64,
     perimeter_centroid: Option<(f64, f64)>
 
@@ -25,6 +32,7 @@ impl PathCount {
             bounds: None,
             running_area: 0.0,
             running_centroid: None,
+            running_second_moment: (0.0, 0.0, 0.0),
             perimeter: 0.0,
             perimeter_centroid: None
         }
@@ -34,21 +42,25 @@ impl PathCount {
         let new = match last {
             None => {
                 let running_area = 0.0;
-                let running_centroid = Some((next.float_x(), next.float_y()));
+                let running_centroid = Some((0.0, 0.0));
+                let running_second_moment = (0.0, 0.0, 0.0);
                 let perimeter = 0.0;
                 let perimeter_centroid = None;
-                PathCount{bounds, running_area, running_centroid, perimeter, perimeter_centroid}
+                PathCount{bounds, running_area, running_centroid, running_second_moment, perimeter, perimeter_centroid}
             },
             Some(last_point) => {
                 let (
                     running_area,
                     running_centroid
                 ) = self.update_area_and_centroid(next, last_point);
+                let running_second_moment = self.update_second_moment(next, last_point);
                 let (
                     perimeter,
                     perimeter_centroid
                 ) = self.update_perimeter(next, last_point);
-                PathCount{bounds, running_area, running_centroid, perimeter, perimeter_centroid}
+                PathCount{
+                    bounds, running_area, running_centroid, running_second_moment, perimeter, perimeter_centroid
+                }
             }
         };
         new
@@ -75,6 +87,16 @@ impl PathCount {
         let running_centroid: (f64, f64) = (x + x_shift, y + y_shift);
         (running_area, Some(running_centroid))
     }
+    fn update_second_moment(&self, next: &Point, last: &Point) -> (f64, f64, f64) {
+        let (x0, y0, x1, y1) = (last.float_x(), last.float_y(), next.float_x(), next.float_y());
+        let increment = x0 * y1 - y0 * x1;
+        let (xx, yy, xy) = self.running_second_moment;
+        (
+            xx + (y0 * y0 + y0 * y1 + y1 * y1) * increment,
+            yy + (x0 * x0 + x0 * x1 + x1 * x1) * increment,
+            xy + (x0 * y1 + 2.0 * x0 * y0 + 2.0 * x1 * y1 + x1 * y0) * increment
+        )
+    }
     fn update_bounds(&self, next: &Point) -> Bounds {
         match &self.bounds {
             None => {
@@ -92,6 +114,21 @@ impl PathCount {
     pub fn area(&self) -> f64 {
         (self.running_area / 2.0).abs()
     }
+    fn first_moment(&self) -> (f64, f64) {
+        match self.running_centroid {
+            Some((rcx, rcy)) if self.running_area != 0f64 => {
+                let sign = self.running_area.signum();
+                (sign * rcx / 6.0, sign * rcy / 6.0)
+            },
+            _ => (0f64, 0f64)
+        }
+    }
+    // Integrals of y², x² and xy over the area, taken about the origin
+    fn second_moment(&self) -> (f64, f64, f64) {
+        let sign = self.running_area.signum();
+        let (xx, yy, xy) = self.running_second_moment;
+        (sign * xx / 12.0, sign * yy / 12.0, sign * xy / 24.0)
+    }
     pub fn centroid(&self) -> Option<Point> {
         match self.running_centroid {
             None => None,
@@ -99,7 +136,8 @@ impl PathCount {
                 let ra = self.running_area;
                 let result = if approx_eq(0f64, ra, 0.001) {
                     if approx_eq(0f64, self.perimeter, 0.001) {
-                        Point::new(rcx.round() as i32, rcy.round() as i32)
+                        let bounds = self.bounds.as_ref().unwrap();
+                        Point::new(bounds.left().to_int(), bounds.top().to_int())
                     } else {
                         let pc = self.perimeter_centroid.unwrap();
                         let pcx = (pc.0 / self.perimeter).round() as i32;
@@ -120,6 +158,137 @@ impl PathCount {
         }
     }
 }
+pub(crate) fn coordinates(point: &Point) -> (i64, i64) {
+    (i64::from(point.x().to_int()), i64::from(point.y().to_int()))
+}
+fn half(d: (i64, i64)) -> i64 {
+    if d.1 > 0 || (d.1 == 0 && d.0 > 0) { 0 } else { 1 }
+}
+pub(crate) fn angular(a: (i64, i64), b: (i64, i64)) -> Ordering {
+    half(a).cmp(&half(b)).then_with(|| (b.0 * a.1).cmp(&(a.0 * b.1)))
+}
+// Whether the edges meeting at a point passed more than once
+// can be paired by visits without the pairs crossing each other
+fn is_straight(a: &Point, b: &Point, c: &Point) -> bool {
+    let (a, b, c) = (coordinates(a), coordinates(b), coordinates(c));
+    (b.0 - a.0) * (c.1 - b.1) == (b.1 - a.1) * (c.0 - b.0)
+}
+fn is_crossing_at(points: &[Point], at: &Point) -> bool {
+    let count = points.len();
+    let center = coordinates(at);
+    let direction = |index: usize| {
+        let (x, y) = coordinates(&points[index % count]);
+        (x - center.0, y - center.1)
+    };
+    let mut ends: Vec<((i64, i64), usize)> = Vec::new();
+    for (index, _) in points.iter().enumerate().filter(|(_, point)| *point == at) {
+        ends.push((direction(index + count - 1), index));
+        ends.push((direction(index + 1), index));
+    }
+    ends.sort_by(|a, b| angular(a.0, b.0));
+    let chords: Vec<(usize, usize)> = ends.iter().enumerate().filter_map(|(position, (_, visit))| {
+        let other = ends.iter().rposition(|(_, other)| other == visit).unwrap();
+        if other > position { Some((position, other)) } else { None }
+    }).collect();
+    chords.iter().any(|(start, end)| chords.iter().any(|(other_start, other_end)| {
+        start < other_start && other_start < end && end < other_end
+    }))
+}
+// Of the two ways to pair arriving and leaving edges without the loops
+// crossing each other, taking always the neighbouring edge on the same side,
+// the one giving more loops is used. Splits into separate parts are preferred
+// over splits into a hole. Points where no pairing helps stay pinched, as do
+// the kept points. Loops are given as the positions of their points in the
+// ring, nothing if the ring wasn't split at all.
+fn pinch_loops(points: &[Point], kept: &[Point]) -> Option<Vec<Vec<usize>>> {
+    fn split(points: &[Point], ring: &[usize], at: &Point) -> Option<Vec<Vec<usize>>> {
+        let count = ring.len();
+        let visits: Vec<usize> = (0..count).filter(|index| &points[ring[*index]] == at).collect();
+        let center = coordinates(at);
+        let direction = |index: usize| {
+            let (x, y) = coordinates(&points[ring[index % count]]);
+            (x - center.0, y - center.1)
+        };
+        // Ends are (direction, visit, arriving)
+        let mut ends: Vec<((i64, i64), usize, bool)> = Vec::new();
+        for (visit, index) in visits.iter().enumerate() {
+            ends.push((direction(index + count - 1), visit, true));
+            ends.push((direction(index + 1), visit, false));
+        }
+        if ends.iter().any(|(direction, _, _)| *direction == (0, 0)) { return None; }
+        ends.sort_by(|a, b| angular(a.0, b.0));
+        let arcs: Vec<Vec<usize>> = (0..visits.len()).map(|visit| {
+            let start = visits[visit];
+            let end = if visit + 1 < visits.len() { visits[visit + 1] } else { visits[0] + count };
+            (start..end).map(|index| ring[index % count]).collect()
+        }).collect();
+        let mut best: Option<Vec<Vec<usize>>> = None;
+        for step in &[1, ends.len() - 1] {
+            let mut leaving = vec![0; visits.len()];
+            let mut valid = true;
+            for (position, (_, visit, arriving)) in ends.iter().enumerate() {
+                let (_, next_visit, next_arriving) = ends[(position + step) % ends.len()];
+                if *arriving {
+                    valid = valid && !next_arriving;
+                    leaving[*visit] = next_visit;
+                }
+            }
+            if !valid { continue; }
+            let mut used = vec![false; arcs.len()];
+            let mut loops: Vec<Vec<usize>> = Vec::new();
+            for first in 0..arcs.len() {
+                if used[first] { continue; }
+                let mut positions = Vec::new();
+                let mut arc = first;
+                while !used[arc] {
+                    used[arc] = true;
+                    positions.extend(arcs[arc].iter().cloned());
+                    arc = leaving[(arc + 1) % arcs.len()];
+                }
+                loops.push(positions);
+            }
+            if loops.len() > best.as_ref().map(|best| best.len()).unwrap_or(1) {
+                best = Some(loops);
+            }
+        }
+        best
+    }
+    fn signed_area(points: &[Point], ring: &[usize]) -> i64 {
+        (0..ring.len()).map(|index| {
+            let (ax, ay) = coordinates(&points[ring[index]]);
+            let (bx, by) = coordinates(&points[ring[(index + 1) % ring.len()]]);
+            ax * by - ay * bx
+        }).sum()
+    }
+    let mut pending: Vec<Vec<usize>> = vec![(0..points.len()).collect()];
+    let mut loops: Vec<Vec<usize>> = Vec::new();
+    let mut changed = false;
+    while let Some(ring) = pending.pop() {
+        let mut seen: HashSet<&Point> = HashSet::new();
+        let repeated: Vec<&Point> = ring.iter()
+            .map(|position| &points[*position])
+            .filter(|point| !seen.insert(*point) && !kept.contains(point))
+            .collect();
+        let sign = signed_area(points, &ring).signum();
+        let splits: Vec<Vec<Vec<usize>>> = repeated.iter().filter_map(|point| split(points, &ring, point)).collect();
+        let separating = splits.iter().position(|split| {
+            split.iter().all(|ring| signed_area(points, ring).signum() == sign)
+        });
+        match splits.into_iter().nth(separating.unwrap_or(0)) {
+            Some(mut split) => {
+                changed = true;
+                pending.append(&mut split);
+            },
+            None => loops.push(ring)
+        }
+    }
+    if changed {
+        loops.reverse();
+        Some(loops)
+    } else {
+        None
+    }
+}
 pub struct PathBuilder {
     points: Vec<Point>,
     count: PathCount
@@ -173,6 +342,46 @@ impl Path {
     }
     pub fn area(&self) -> f64 { self.count.area() }
     pub fn centroid(&self) -> Option<Point> { self.count.centroid() }
+    pub(crate) fn first_moment(&self) -> (f64, f64) { self.count.first_moment() }
+    pub(crate) fn second_moment(&self) -> (f64, f64, f64) { self.count.second_moment() }
+    pub fn perimeter(&self) -> f64 { self.count.perimeter }
+    // Ix, Iy and Ixy about the centroid
+    pub fn second_moments(&self) -> (f64, f64, f64) {
+        metrics::centroidal_moments(self.area(), self.first_moment(), self.second_moment())
+    }
+    // Ratio of the area to the area of the convex hull
+    pub fn convexity(&self) -> f64 {
+        let hull_area = self.convex_hull().area();
+        if hull_area == 0f64 { 1f64 } else { self.area() / hull_area }
+    }
+    // Oriented as an outer path, points lying on hull edges left out
+    pub fn convex_hull(&self) -> Path {
+        Path::new(&metrics::convex_hull(self.points.iter()))
+    }
+    // Isoperimetric quotient, one for a circle
+    pub fn compactness(&self) -> f64 {
+        let perimeter = self.perimeter();
+        if perimeter == 0f64 { 0f64 } else { 4.0 * std::f64::consts::PI * self.area() / (perimeter * perimeter) }
+    }
+    pub fn bounding_circle_radius(&self) -> f64 {
+        metrics::bounding_circle_radius(self.points.iter())
+    }
+    // Closest point of the boundary, the path being closed
+    pub fn closest_point(&self, point: &Point) -> Option<(f64, f64)> {
+        self.segments()
+            .map(|segment| (segment.distance_to_point(point), segment.closest_point(point)))
+            .fold(None, |best: Option<(f64, (f64, f64))>, next| match best {
+                Some(best) if best.0 <= next.0 => Some(best),
+                _ => Some(next)
+            })
+            .map(|(_, closest)| closest)
+    }
+    pub(crate) fn boundary_distance(&self, point: &Point) -> f64 {
+        self.segments().map(|segment| segment.distance_to_point(point)).fold(f64::INFINITY, f64::min)
+    }
+    pub(crate) fn segments(&self) -> impl Iterator<Item = Straight> + '_ {
+        (0..self.points.len()).map(move |index| self.segment_at(index).unwrap())
+    }
     pub fn bounds(&self) -> Option<&Bounds> {
         match &self.count.bounds {
             None => None,
@@ -198,6 +407,11 @@ impl Path {
         let points = self.points.drain(..).rev().collect();
         Path::new(&points)
     }
+    pub fn transform(&self, transform: &Transform) -> Result<Path, Error> {
+        let points = self.points.iter().map(|point| transform.apply(point)).collect::<Result<Vec<Point>, Error>>()?;
+        let path = Path::new(&points);
+        if transform.is_mirroring() { Ok(path.reverse()) } else { Ok(path) }
+    }
     pub fn inspect(&self) -> String {
         let strings: Vec<String> = self.points()
             .iter()
@@ -205,6 +419,199 @@ impl Path {
             .collect();
         strings.join(", ")
     }
+    fn key(point: &Point) -> (Coordinate, Coordinate) {
+        (point.x(), point.y())
+    }
+    fn rotation(&self, start: usize) -> impl Iterator<Item=(Coordinate, Coordinate)> + '_ {
+        self.points[start..].iter().chain(self.points[..start].iter()).map(Path::key)
+    }
+    pub fn canonical_order(a: &Path, b: &Path) -> Ordering {
+        a.rotation(0).cmp(b.rotation(0))
+    }
+    // Starts the path at its leftmost point, the upper one of a tie. If the
+    // path passes that point more than once, the smallest rotation wins.
+    pub fn canonical(self) -> Path {
+        let lowest = self.points.iter().map(Path::key).min();
+        let start = (0..self.points.len())
+            .filter(|index| Some(Path::key(&self.points[*index])) == lowest)
+            .min_by(|a, b| self.rotation(*a).cmp(self.rotation(*b)));
+        match start {
+            Some(start) if start > 0 => {
+                let mut points = self.points;
+                points.rotate_left(start);
+                Path { points, count: self.count }
+            },
+            _ => self
+        }
+    }
+    // Exact position of a point given in doubled coordinates,
+    // makes it possible to test midpoints between grid points
+    pub(crate) fn position_doubled(&self, x: i64, y: i64) -> Position {
+        let doubled = |point: &Point| {
+            (2 * i64::from(point.x().to_int()), 2 * i64::from(point.y().to_int()))
+        };
+        let mut position = Position::Out;
+        for index in 0..self.points.len() {
+            let (sx, sy) = doubled(&self.points[index]);
+            let (ex, ey) = doubled(&self.points[(index + 1) % self.points.len()]);
+            let cross = (ex - sx) * (y - sy) - (ey - sy) * (x - sx);
+            if cross == 0 && sx.min(ex) <= x && x <= sx.max(ex) && sy.min(ey) <= y && y <= sy.max(ey) {
+                return Position::Edge;
+            }
+            if (sy < y && ey >= y) || (ey < y && sy >= y) {
+                let to_the_left = if sy < ey { cross < 0 } else { cross > 0 };
+                if to_the_left {
+                    position = Position::invert(&position);
+                }
+            }
+        }
+        position
+    }
+    // Drops repeated points and points where the path doesn't turn, that is
+    // midpoints of collinear segments as well as tips of zero-area spikes
+    pub fn cleanup(self) -> Path {
+        self.cleanup_except(|_| false)
+    }
+    // Same, keeping the given points where the path passes them
+    pub(crate) fn cleanup_except<K: Fn(&Point) -> bool>(self, kept: K) -> Path {
+        let mut points: Vec<Point> = Vec::with_capacity(self.points.len());
+        for point in self.points.iter() {
+            while points.len() > 1 &&
+                !kept(&points[points.len() - 1]) &&
+                is_straight(&points[points.len() - 2], &points[points.len() - 1], point) {
+                points.pop();
+            }
+            if points.last() != Some(point) {
+                points.push(point.clone());
+            }
+        }
+        loop {
+            let count = points.len();
+            if (count > 1 && points[0] == points[count - 1]) ||
+                (count > 2 && !kept(&points[count - 1]) && is_straight(&points[count - 2], &points[count - 1], &points[0])) {
+                points.pop();
+            } else if count > 2 && !kept(&points[0]) && is_straight(&points[count - 1], &points[0], &points[1]) {
+                points.remove(0);
+            } else {
+                break;
+            }
+        }
+        if points.len() == self.points.len() {
+            self
+        } else {
+            Path::new(&points)
+        }
+    }
+    // A path passing a point more than once is cut there into loops. Points
+    // lying on an edge of the same path are made its vertices first.
+    pub fn split_pinches(self) -> Vec<Path> {
+        match self.with_points_on_edges(&self.points).split_pinches_except(&[]) {
+            Some(paths) => paths,
+            None => vec![self]
+        }
+    }
+    // Loops of the path split at its pinches, see pinch_loops
+    pub(crate) fn split_pinches_except(&self, kept: &[Point]) -> Option<Vec<Path>> {
+        let loops = pinch_loops(&self.points, kept)?;
+        Some(loops.iter().map(|positions| {
+            Path::new(&positions.iter().map(|position| self.points[*position].clone()).collect())
+        }).collect())
+    }
+    // Points where the path turns
+    pub(crate) fn corners(&self) -> impl Iterator<Item = &Point> + '_ {
+        let count = self.points.len();
+        (0..count)
+            .filter(move |index| {
+                let (previous, next) = (&self.points[(index + count - 1) % count], &self.points[(index + 1) % count]);
+                !is_straight(previous, &self.points[*index], next)
+            })
+            .map(move |index| &self.points[index])
+    }
+    // Points of the candidates lying inside an edge are inserted there
+    pub(crate) fn with_points_on_edges(&self, candidates: &[Point]) -> Path {
+        let points = &self.points;
+        let count = points.len();
+        let mut result: Vec<Point> = Vec::with_capacity(count);
+        for index in 0..count {
+            let (start, end) = (&points[index], &points[(index + 1) % count]);
+            let (a, b) = (coordinates(start), coordinates(end));
+            let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+            let length = dx * dx + dy * dy;
+            let mut inside: Vec<(i64, &Point)> = candidates.iter().filter_map(|point| {
+                let (x, y) = coordinates(point);
+                let along = (x - a.0) * dx + (y - a.1) * dy;
+                let is_inside = (x - a.0) * dy == (y - a.1) * dx && along > 0 && along < length;
+                if is_inside { Some((along, point)) } else { None }
+            }).collect();
+            inside.sort_by_key(|(along, _)| *along);
+            inside.dedup_by_key(|(along, _)| *along);
+            result.push(start.clone());
+            result.extend(inside.into_iter().map(|(_, point)| point.clone()));
+        }
+        if result.len() == count {
+            self.clone()
+        } else {
+            Path::new(&result)
+        }
+    }
+    // Both paths are passed in one, going around the other path where this
+    // one passes the given point. Of several such places, one where
+    // the paths don't cross is taken.
+    pub(crate) fn joined(&self, other: &Path, at: &Point) -> Path {
+        let starts = |points: &Vec<Point>| -> Vec<usize> {
+            (0..points.len()).filter(|index| &points[*index] == at).collect()
+        };
+        let mut first: Option<Vec<Point>> = None;
+        for start in starts(&self.points) {
+            for other_start in starts(&other.points) {
+                let mut points = self.points.clone();
+                points.rotate_left(start);
+                let mut other_points = other.points.clone();
+                other_points.rotate_left(other_start);
+                points.append(&mut other_points);
+                if !is_crossing_at(&points, at) {
+                    return Path::new(&points);
+                }
+                first = first.or(Some(points));
+            }
+        }
+        Path::new(&first.unwrap_or_else(|| self.points.clone()))
+    }
+    // Every turn to the same side is not enough, a star turns consistently
+    // too, but the direction along x changes its sign more than twice
+    pub(crate) fn is_strictly_convex(&self) -> bool {
+        let count = self.points.len();
+        if count < 3 { return false; }
+        let directions: Vec<(i64, i64)> = (0..count).map(|index| {
+            let start = &self.points[index];
+            let end = &self.points[(index + 1) % count];
+            (
+                i64::from(end.x().to_int()) - i64::from(start.x().to_int()),
+                i64::from(end.y().to_int()) - i64::from(start.y().to_int())
+            )
+        }).collect();
+        let mut turn = 0;
+        for index in 0..count {
+            let (a, b) = (directions[index], directions[(index + 1) % count]);
+            let cross = (a.0 * b.1 - a.1 * b.0).signum();
+            if cross == 0 || (turn != 0 && cross != turn) { return false; }
+            turn = cross;
+        }
+        let signs: Vec<i64> = directions.iter().map(|(x, _)| x.signum()).filter(|sign| *sign != 0).collect();
+        let changes = (0..signs.len()).filter(|index| signs[*index] != signs[(index + 1) % signs.len()]).count();
+        changes == 2
+    }
+}
+impl PartialEq for Path {
+    fn eq(&self, other: &Path) -> bool {
+        self.points == other.points
+    }
+}
+impl Eq for Path {}
+impl Hash for Path {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.points.hash(state);
+    }
 }
 impl Clone for Path {
     fn clone(&self) -> Path {
@@ -217,41 +624,33 @@ impl Clone for Path {
 }
 impl Shape for Path {
     fn position(&self, point: &Point) -> Position {
-        fn is_to_the_right(p: &Point, s: &Straight) -> bool {
-            s.start.x() <= p.x() || s.end.x() <= p.x()
-        }
         fn is_within_points_on_y(p: &Point, s: &Straight) -> bool {
             if s.start.y() < p.y() && s.end.y() >= p.y() {return true;}
             if s.end.y() < p.y() && s.start.y() >= p.y() {return true;}
             return false;
         }
-        fn intersects_with(l1: &Line, s: &Straight) -> bool {
-            if l1.contains(&s.start) {return true};
-            if l1.contains(&s.end) {return true};
-            let l2 = s.to_line();
-            let i1 = Line::intersection(l1, &l2);
-            match i1 {
-                None => false,
-                Some(intersection) => s.bounds.contains(&intersection, &Mode::Closed)
-            }
+        fn crosses_to_the_left(p: &Point, s: &Straight) -> bool {
+            let (upper, lower) = if s.start.y() < s.end.y() {
+                (&s.start, &s.end)
+            } else {
+                (&s.end, &s.start)
+            };
+            let dx = i64::from((lower.x() - upper.x()).to_int());
+            let dy = i64::from((lower.y() - upper.y()).to_int());
+            let px = i64::from((p.x() - upper.x()).to_int());
+            let py = i64::from((p.y() - upper.y()).to_int());
+            dx * py < dy * px
         }
         let mut position = Position::Out;
         let mut i: usize = 0;
 
-        let base = Vector::from(&Point::unchecked(
-            Coordinate::new(1000),
-            Coordinate::new(0)
-        ));
-
-        let line = Line::new(&point, &base);
         while position != Position::Edge && i < self.points.len() {
             let segment = self.segment_at(i).unwrap();
             if segment.contains(point, &Mode::Closed) {
                 position = Position::Edge;
             } else if
-                is_to_the_right(&point, &segment) &&
-                    is_within_points_on_y(&point, &segment) &&
-                    intersects_with(&line, &segment)
+                is_within_points_on_y(point, &segment) &&
+                    crosses_to_the_left(point, &segment)
                 {
                     position = Position::invert(&position);
                 }
@@ -312,6 +711,20 @@ fn centroid_test() {
     let path5 = Path::new(&points5);
     assert_eq!(path5.centroid().unwrap(), Point::new(300, 250).expect("!"));
 }
+// The first point used to be counted into the sums of the shoelace
+// formula, shifting centroids of small paths far from the origin
+#[test]
+fn centroid_offset_test() {
+    let path = |points: Vec<(i32, i32)>| Path::new(
+        &points.into_iter().map(|(x, y)| Point::new(x, y).expect("!")).collect()
+    );
+    let triangle = path(vec![(1000, 1000), (1003, 1000), (1000, 1003)]);
+    assert_eq!(triangle.centroid().unwrap(), Point::new(1001, 1001).expect("!"));
+    let square = path(vec![(-5000, 7000), (-4998, 7000), (-4998, 7002), (-5000, 7002)]);
+    assert_eq!(square.centroid().unwrap(), Point::new(-4999, 7001).expect("!"));
+    let single = path(vec![(700, -300)]);
+    assert_eq!(single.centroid().unwrap(), Point::new(700, -300).expect("!"));
+}
 #[test]
 fn direction_test() {
     let points1 = vec![
@@ -356,55 +769,196 @@ fn path_test() {
     assert_eq!(path.bounds().unwrap(), &Bounds::new(0, 0, 20, 40));
 
     let pc = Point::new(20, 10).expect("!");
-    assert!(path.contains(&pc, &Mode::Closed));
-    assert!(path.contains(&pc, &Mode::Open));
+    assert!(path.contains(&pc, &Mode::Closed).unwrap());
+    assert!(path.contains(&pc, &Mode::Open).unwrap());
 
     let tp0 = Point::new(20, -1).expect("!");
-    assert!(!path.contains(&tp0, &Mode::Closed));
-    assert!(!path.contains(&tp0, &Mode::Open));
+    assert!(!path.contains(&tp0, &Mode::Closed).unwrap());
+    assert!(!path.contains(&tp0, &Mode::Open).unwrap());
 
     let tp1 = Point::new(20, 0).expect("!");
-    assert!(path.contains(&tp1, &Mode::Closed));
-    assert!(!path.contains(&tp1, &Mode::Open));
+    assert!(path.contains(&tp1, &Mode::Closed).unwrap());
+    assert!(!path.contains(&tp1, &Mode::Open).unwrap());
 
     let tp2 = Point::new(20, 1).expect("!");
-    assert!(path.contains(&tp2, &Mode::Closed));
-    assert!(path.contains(&tp2, &Mode::Open));
+    assert!(path.contains(&tp2, &Mode::Closed).unwrap());
+    assert!(path.contains(&tp2, &Mode::Open).unwrap());
 
     let lp0 = Point::new(-1, 10).expect("!");
-    assert!(!path.contains(&lp0, &Mode::Closed));
-    assert!(!path.contains(&lp0, &Mode::Open));
+    assert!(!path.contains(&lp0, &Mode::Closed).unwrap());
+    assert!(!path.contains(&lp0, &Mode::Open).unwrap());
 
     let lp1 = Point::new(5, 10).expect("!");
-    assert!(path.contains(&lp1, &Mode::Closed));
-    assert!(!path.contains(&lp1, &Mode::Open));
+    assert!(path.contains(&lp1, &Mode::Closed).unwrap());
+    assert!(!path.contains(&lp1, &Mode::Open).unwrap());
 
     let lp2 = Point::new(11, 10).expect("!");
-    assert!(path.contains(&lp2, &Mode::Closed));
-    assert!(path.contains(&lp2, &Mode::Open));
+    assert!(path.contains(&lp2, &Mode::Closed).unwrap());
+    assert!(path.contains(&lp2, &Mode::Open).unwrap());
 
     let bp0 = Point::new(20, 21).expect("!");
-    assert!(!path.contains(&bp0, &Mode::Closed));
-    assert!(!path.contains(&bp0, &Mode::Open));
+    assert!(!path.contains(&bp0, &Mode::Closed).unwrap());
+    assert!(!path.contains(&bp0, &Mode::Open).unwrap());
 
     let bp1 = Point::new(20, 20).expect("!");
-    assert!(path.contains(&bp1, &Mode::Closed));
-    assert!(!path.contains(&bp1, &Mode::Open));
+    assert!(path.contains(&bp1, &Mode::Closed).unwrap());
+    assert!(!path.contains(&bp1, &Mode::Open).unwrap());
 
     let bp2 = Point::new(20, 19).expect("!");
-    assert!(path.contains(&bp2, &Mode::Closed));
-    assert!(path.contains(&bp2, &Mode::Open));
+    assert!(path.contains(&bp2, &Mode::Closed).unwrap());
+    assert!(path.contains(&bp2, &Mode::Open).unwrap());
 
     let rp0 = Point::new(41, 10).expect("!");
-    assert!(!path.contains(&rp0, &Mode::Closed));
-    assert!(!path.contains(&rp0, &Mode::Open));
+    assert!(!path.contains(&rp0, &Mode::Closed).unwrap());
+    assert!(!path.contains(&rp0, &Mode::Open).unwrap());
 
     let rp1 = Point::new(35, 10).expect("!");
-    assert!(path.contains(&rp1, &Mode::Closed));
-    assert!(!path.contains(&rp1, &Mode::Open));
+    assert!(path.contains(&rp1, &Mode::Closed).unwrap());
+    assert!(!path.contains(&rp1, &Mode::Open).unwrap());
 
     let rp2 = Point::new(29, 10).expect("!");
-    assert!(path.contains(&rp2, &Mode::Closed));
-    assert!(path.contains(&rp2, &Mode::Open));
+    assert!(path.contains(&rp2, &Mode::Closed).unwrap());
+    assert!(path.contains(&rp2, &Mode::Open).unwrap());
+
+}
+#[test]
+fn slanted_edge_position_test() {
+    let points = vec![
+        Point::new(100, 0).expect("!"),
+        Point::new(200, 100).expect("!"),
+        Point::new(0, 100).expect("!")
+    ];
+    let path = Path::new(&points);
+    assert_eq!(path.position(&Point::new(100, 50).expect("!")), Position::In);
+    assert_eq!(path.position(&Point::new(20, 50).expect("!")), Position::Out);
+    assert_eq!(path.position(&Point::new(180, 50).expect("!")), Position::Out);
+    assert_eq!(path.position(&Point::new(50, 50).expect("!")), Position::Edge);
+}
+#[test]
+fn canonical_test() {
+    let points = vec![
+        Point::new(4, 0).expect("!"),
+        Point::new(4, 4).expect("!"),
+        Point::new(0, 4).expect("!"),
+        Point::new(0, 0).expect("!")
+    ];
+    let path = Path::new(&points).canonical();
+    assert_eq!(path.points()[0], Point::new(0, 0).expect("!"));
+    assert_eq!(path.points()[1], Point::new(4, 0).expect("!"));
+    assert_eq!(path.area(), 16.0);
 
+    let pinched = vec![
+        Point::new(0, 0).expect("!"),
+        Point::new(4, 2).expect("!"),
+        Point::new(4, 4).expect("!"),
+        Point::new(0, 0).expect("!"),
+        Point::new(4, -2).expect("!"),
+        Point::new(4, 0).expect("!")
+    ];
+    let path = Path::new(&pinched).canonical();
+    assert_eq!(path.points()[0], Point::new(0, 0).expect("!"));
+    assert_eq!(path.points()[1], Point::new(4, -2).expect("!"));
+    let rotated = Path::new(&pinched[1..].iter().chain(pinched[..1].iter()).cloned().collect());
+    assert!(path == rotated.canonical());
+}
+#[test]
+fn strictly_convex_test() {
+    let path = |points: Vec<(i32, i32)>| {
+        Path::new(&points.into_iter().map(|(x, y)| Point::new(x, y).expect("!")).collect())
+    };
+    assert!(path(vec![(0, 0), (4, 0), (4, 4), (0, 4)]).is_strictly_convex());
+    assert!(path(vec![(0, 4), (4, 4), (4, 0), (0, 0)]).is_strictly_convex());
+    assert!(!path(vec![(0, 0), (2, 0), (4, 0), (4, 4), (0, 4)]).is_strictly_convex());
+    assert!(!path(vec![(0, 0), (4, 0), (2, 1), (4, 4), (0, 4)]).is_strictly_convex());
+    assert!(!path(vec![(0, 0), (4, 0)]).is_strictly_convex());
+    let star = path(vec![(0, -10), (6, 8), (-9, -3), (9, -3), (-6, 8)]);
+    assert!(!star.is_strictly_convex());
+}
+#[test]
+fn cleanup_test() {
+    let path = |points: Vec<(i32, i32)>| {
+        Path::new(&points.into_iter().map(|(x, y)| Point::new(x, y).expect("!")).collect())
+    };
+    let square = path(vec![(0, 0), (4, 0), (4, 4), (0, 4)]);
+    assert!(square.clone().cleanup() == square);
+    let collinear = path(vec![(2, 0), (4, 0), (4, 2), (4, 4), (0, 4), (0, 0), (0, 0)]);
+    assert!(collinear.cleanup() == path(vec![(4, 0), (4, 4), (0, 4), (0, 0)]));
+    let spike = path(vec![(0, 0), (4, 0), (4, 4), (6, 6), (4, 4), (0, 4)]);
+    let cleaned = spike.cleanup();
+    assert!(cleaned == path(vec![(0, 0), (4, 0), (4, 4), (0, 4)]));
+    assert_eq!(cleaned.area(), 16.0);
+    let closing = path(vec![(0, 0), (4, 0), (4, 4), (0, 4), (0, 0)]);
+    assert_eq!(closing.cleanup().points().len(), 4);
+    let pinched = path(vec![(0, 0), (4, 0), (4, 4), (0, 0), (-4, 0), (-4, -4)]);
+    assert_eq!(pinched.cleanup().points().len(), 6);
+}
+#[test]
+fn split_pinches_test() {
+    let path = |points: Vec<(i32, i32)>| {
+        Path::new(&points.into_iter().map(|(x, y)| Point::new(x, y).expect("!")).collect())
+    };
+    let bow = path(vec![(-10, -10), (0, 0), (10, -10), (10, 10), (0, 0), (-10, 10)]);
+    let split = bow.split_pinches();
+    assert_eq!(split.len(), 2);
+    assert_eq!(split.iter().map(|path| path.points().len()).collect::<Vec<usize>>(), vec![3, 3]);
+    assert_eq!(split.iter().map(|path| path.area()).sum::<f64>(), 200.0);
+
+    let keyhole = path(vec![(0, 0), (5, 0), (3, 5), (7, 5), (5, 0), (10, 0), (10, 10), (0, 10)]);
+    let split = keyhole.split_pinches();
+    assert_eq!(split.len(), 2);
+    let areas: Vec<f64> = split.iter().map(|path| path.area()).collect();
+    assert!(areas == vec![10.0, 100.0] || areas == vec![100.0, 10.0]);
+
+    let cut = path(vec![(0, 0), (10, 0), (10, 5), (5, 2), (0, 5), (5, 8), (10, 5), (10, 10), (0, 10)]);
+    let split = cut.split_pinches();
+    assert_eq!(split.len(), 2);
+    assert!(split.iter().all(|path| path.area() == 35.0 && path.direction() == PathDirection::Clockwise));
+
+    let square = path(vec![(0, 0), (4, 0), (4, 4), (0, 4)]);
+    assert!(square.clone().split_pinches() == vec![square]);
+}
+#[test]
+fn metrics_test() {
+    let path = |points: Vec<(i32, i32)>| Path::new(
+        &points.into_iter().map(|(x, y)| Point::new(x, y).unwrap()).collect()
+    );
+    let close = |a: f64, b: f64| approx_eq(a, b, 1e-9);
+    let rectangle = path(vec![(10, 10), (14, 10), (14, 12), (10, 12)]);
+    assert_eq!(rectangle.perimeter(), 12.0);
+    let (ix, iy, ixy) = rectangle.second_moments();
+    assert!(close(ix, 8.0 / 3.0) && close(iy, 32.0 / 3.0) && close(ixy, 0.0));
+    assert!(rectangle.clone().reverse().second_moments() == rectangle.second_moments());
+    assert_eq!(rectangle.convexity(), 1.0);
+    assert!(close(rectangle.compactness(), 4.0 * std::f64::consts::PI * 8.0 / 144.0));
+    assert!(close(rectangle.bounding_circle_radius(), 5f64.sqrt()));
+
+    let triangle = path(vec![(0, 0), (6, 0), (0, 6)]);
+    let (ix, iy, ixy) = triangle.second_moments();
+    assert!(close(ix, 36.0) && close(iy, 36.0) && close(ixy, -18.0));
+
+    let notched = path(vec![(0, 0), (4, 0), (4, 4), (2, 2), (0, 4)]);
+    assert_eq!(notched.convexity(), 0.75);
+}
+#[test]
+fn convex_hull_test() {
+    let path = |points: Vec<(i32, i32)>| Path::new(
+        &points.into_iter().map(|(x, y)| Point::new(x, y).unwrap()).collect()
+    );
+    let notched = path(vec![(0, 4), (2, 2), (4, 4), (4, 0), (2, 0), (0, 0)]);
+    let hull = notched.convex_hull();
+    assert!(hull == path(vec![(0, 0), (4, 0), (4, 4), (0, 4)]));
+    assert_eq!(hull.direction(), PathDirection::Clockwise);
+    let segment = path(vec![(0, 0), (2, 2), (4, 4), (1, 1)]);
+    assert!(segment.convex_hull() == path(vec![(0, 0), (4, 4)]));
+}
+
+#[test]
+fn closest_point_test() {
+    let path = Path::new(&vec![(0, 0), (4, 0), (4, 4), (0, 4)].into_iter()
+        .map(|(x, y)| Point::new(x, y).unwrap()).collect());
+    assert_eq!(path.closest_point(&Point::new(6, 2).unwrap()), Some((4f64, 2f64)));
+    assert_eq!(path.closest_point(&Point::new(1, 2).unwrap()), Some((0f64, 2f64)));
+    assert_eq!(path.closest_point(&Point::new(-3, 8).unwrap()), Some((0f64, 4f64)));
+    assert_eq!(path.boundary_distance(&Point::new(-3, 8).unwrap()), 5f64);
+    assert_eq!(Path::new(&vec![]).closest_point(&Point::new(1, 1).unwrap()), None);
 }
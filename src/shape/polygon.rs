@@ -1,8 +1,12 @@
-use crate::primitives::{Bounds, AbstractPoint, Point, Position};
+use crate::primitives::{Bounds, AbstractPoint, Point, Position, Straight, Mode, Sector};
 use crate::shape::{Shape};
 use crate::shape::{Path, PathDirection};
 use super::triangular_matrix::{TriangularMatrix};
+use super::metrics;
+use crate::transform::Transform;
 use crate::Error;
+use std::hash::{Hash, Hasher};
+use std::collections::{HashMap, HashSet};
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum Relation {
@@ -11,7 +15,9 @@ pub enum Relation {
     PartlyContained,
     Contains,
     Contained,
-    Identical
+    Identical,
+    // Sharing boundary points but no area
+    Touching
 }
 impl Relation {
     pub fn invert(value: Relation) -> Relation {
@@ -21,7 +27,8 @@ impl Relation {
             Relation::PartlyContained => Relation::PartlyContained,
             Relation::Contained => Relation::Contains,
             Relation::Contains => Relation::Contained,
-            Relation::Identical => Relation::Identical
+            Relation::Identical => Relation::Identical,
+            Relation::Touching => Relation::Touching
         }
     }
 }
@@ -30,12 +37,20 @@ impl Relation {
 struct PolygonCount {
     bounds: Option<Bounds>,
     running_area: f64,
-    running_centroid: Option<(f64, f64)>
+    running_centroid: Option<(f64, f64)>,
+    running_second_moment: (f64, f64, f64),
+    perimeter: f64
 }
 
 impl PolygonCount {
     pub fn new() -> PolygonCount {
-        PolygonCount{bounds: None, running_area: 0f64, running_centroid: None}
+        PolygonCount{
+            bounds: None,
+            running_area: 0f64,
+            running_centroid: None,
+            running_second_moment: (0f64, 0f64, 0f64),
+            perimeter: 0f64
+        }
     }
     #[allow(dead_code)]
     fn inspect(&self) -> String {
@@ -71,44 +86,33 @@ impl PolygonCount {
     }
     fn update (&self, path: &Path, level: usize) -> PolygonCount {
         if path.is_null() { return self.clone(); }
-        match &self.bounds {
-            None => {
-                if level > 0 { panic!("Zero level expected, got {}", level)}
-                let bounds = Some(path.bounds().expect("Expected bounds to be there").clone());
-                let running_area = path.area();
-                let centroid = path.centroid().expect("Expected centroid to be there");
-                let rcx = centroid.float_x() * running_area;
-                let rcy = centroid.float_y() * running_area;
-                let running_centroid = Some((rcx, rcy));
-                PolygonCount{bounds, running_area, running_centroid}
-            },
-            Some(ref old) => {
-                let bounds = if level == 0 {
-                    let new = Bounds::union(old, &path.bounds().unwrap());
-                    Some(new)
-                } else {
-                    Some(old.clone())
-                };
-                let rc = self.running_centroid.unwrap();
-                let rcx_incr = path.centroid().unwrap().float_x() * path.area();
-                let rcy_incr = path.centroid().unwrap().float_y() * path.area();
-                let (running_area, running_centroid) = if level % 2 == 0 {
-                    let ra = self.running_area + path.area();
-                    let rcx = rc.0 + rcx_incr;
-                    let rcy = rc.1 + rcy_incr;
-                    (ra, Some((rcx, rcy)))
-                } else {
-                    let ra = self.running_area - path.area();
-                    let rcx = rc.0 - rcx_incr;
-                    let rcy = rc.1 - rcy_incr;
-                    (ra, Some((rcx, rcy)))
-                };
-                PolygonCount{bounds, running_area, running_centroid}
-            }
-        }
+        let bounds = match (&self.bounds, path.bounds()) {
+            (Some(old), Some(path_bounds)) if level == 0 => Some(Bounds::union(old, path_bounds)),
+            (None, Some(path_bounds)) if level == 0 => Some(path_bounds.clone()),
+            (old, _) => old.clone()
+        };
+        let rc = self.running_centroid.unwrap_or((0f64, 0f64));
+        let (rcx_incr, rcy_incr) = path.first_moment();
+        let (running_area, running_centroid) = if level % 2 == 0 {
+            let ra = self.running_area + path.area();
+            let rcx = rc.0 + rcx_incr;
+            let rcy = rc.1 + rcy_incr;
+            (ra, Some((rcx, rcy)))
+        } else {
+            let ra = self.running_area - path.area();
+            let rcx = rc.0 - rcx_incr;
+            let rcy = rc.1 - rcy_incr;
+            (ra, Some((rcx, rcy)))
+        };
+        let sign = if level % 2 == 1 { -1f64 } else { 1f64 };
+        let (xx, yy, xy) = self.running_second_moment;
+        let (xx_incr, yy_incr, xy_incr) = path.second_moment();
+        let running_second_moment = (xx + sign * xx_incr, yy + sign * yy_incr, xy + sign * xy_incr);
+        let perimeter = self.perimeter + path.perimeter();
+        PolygonCount{bounds, running_area, running_centroid, running_second_moment, perimeter}
     }
 }
-#[derive(Clone)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
 pub struct Record {
     level: usize,
     parent: Option<usize>
@@ -146,12 +150,15 @@ impl PolygonStructure {
         let levels = vec![zero];
         PolygonStructure{table, records, levels}
     }
-    pub fn build(paths: &Vec<Path>, comparator: &mut dyn Comparator) -> Result<PolygonStructure, &'static str> {
-        let mut table = TriangularMatrix::new(
+    pub fn build(paths: &Vec<Path>, comparator: &mut dyn Comparator) -> Result<PolygonStructure, Error> {
+        let mut table = match TriangularMatrix::new(
             paths.len(),
             Relation::Unknown,
             Relation::invert
-        )?;
+        ) {
+            Ok(table) => table,
+            Err(_) => return Err(Error::TooManyPathsError)
+        };
         let mut records = (0..paths.len()).map(|_: usize| Record::new()).collect();
         let mut levels: Vec< Vec<usize>> = Vec::new();
         for index in 0..paths.len() {
@@ -162,7 +169,7 @@ impl PolygonStructure {
                 &mut records,
                 &mut levels,
                 comparator
-            );
+            )?;
         }
         PolygonStructure::build_levels(&mut table, &mut records, &mut levels);
         Ok(PolygonStructure{table, records, levels})
@@ -174,7 +181,7 @@ impl PolygonStructure {
         records: &mut Vec<Record>,
         levels: &mut Vec< Vec<usize> >,
         comparator: &mut dyn Comparator
-    ) {
+    ) -> Result<(), Error> {
         let path = &paths[index];
         let mut unrelated: Vec<usize> = Vec::new();
         let mut contained: Vec<usize> = Vec::new();
@@ -212,7 +219,9 @@ impl PolygonStructure {
                         table
                     );
                 },
-                _ => panic!("Unexpected relation: {:?}", relation)
+                _ => return Err(Error::FatalError(
+                    format!("Unexpected relation between paths {} and {}: {:?}", index, other_index, relation)
+                ))
             }
         }
         let level = records[index].level;
@@ -220,6 +229,7 @@ impl PolygonStructure {
             levels.push(Vec::new());
         }
         levels[level].push(index);
+        Ok(())
     }
     fn set_relations(
         index: usize,
@@ -275,6 +285,43 @@ impl Comparator for FlatComparator {
     }
 
 }
+// For paths that don't cross each other, may touch though
+pub struct GeometricComparator {}
+impl GeometricComparator {
+    fn position(outer: &Path, inner: &Path) -> Position {
+        let points = inner.points();
+        let vertices = points.iter().map(|point| {
+            outer.position_doubled(2 * i64::from(point.x().to_int()), 2 * i64::from(point.y().to_int()))
+        });
+        let midpoints = (0..points.len()).map(|index| {
+            let (start, end) = (&points[index], &points[(index + 1) % points.len()]);
+            outer.position_doubled(
+                i64::from(start.x().to_int()) + i64::from(end.x().to_int()),
+                i64::from(start.y().to_int()) + i64::from(end.y().to_int())
+            )
+        });
+        vertices.chain(midpoints)
+            .find(|position| *position != Position::Edge)
+            .unwrap_or(Position::Edge)
+    }
+}
+impl Comparator for GeometricComparator {
+    fn compare(&mut self, a: &Path, b: &Path, _: usize, _: usize) -> Relation {
+        let collision = match (a.bounds(), b.bounds()) {
+            (Some(a_bounds), Some(b_bounds)) => Bounds::have_collision(a_bounds, b_bounds),
+            _ => false
+        };
+        if !collision {
+            Relation::Unrelated
+        } else if GeometricComparator::position(a, b) == Position::In {
+            Relation::Contains
+        } else if GeometricComparator::position(b, a) == Position::In {
+            Relation::Contained
+        } else {
+            Relation::Unrelated
+        }
+    }
+}
 #[derive(Clone)]
 pub struct Polygon {
     paths: Vec<Path>,
@@ -300,23 +347,19 @@ impl Polygon {
         }
     }
     pub fn build(mut paths: Vec<Path>, comparator: &mut dyn Comparator) -> Result<Polygon, Error> {
-        let result = PolygonStructure::build(&paths, comparator);
-        if let Ok(structure) = result {
-            let mut count = PolygonCount::new();
-            let normalized: Vec<Path> = paths.drain(..).enumerate().map(|(path_index, path)| {
-                let depth = structure.records[path_index].level;
-                count = count.update(&path, depth);
-                Polygon::normalize(path, depth)
-            }).collect();
-            Ok(Polygon{
-                paths: normalized,
-                structure: structure.records,
-                levels: structure.levels,
-                count
-            })
-        } else {
-            Err(Error::TooManyPathsError)
-        }
+        let structure = PolygonStructure::build(&paths, comparator)?;
+        let mut count = PolygonCount::new();
+        let normalized: Vec<Path> = paths.drain(..).enumerate().map(|(path_index, path)| {
+            let depth = structure.records[path_index].level;
+            count = count.update(&path, depth);
+            Polygon::normalize(path, depth)
+        }).collect();
+        Ok(Polygon{
+            paths: normalized,
+            structure: structure.records,
+            levels: structure.levels,
+            count
+        })
     }
     pub fn centroid(&self) -> Option<Point> {
         self.count.centroid()
@@ -324,6 +367,69 @@ impl Polygon {
     pub fn area(&self) -> f64 {
         self.count.area()
     }
+    // Holes count into the perimeter too
+    pub fn perimeter(&self) -> f64 {
+        self.count.perimeter
+    }
+    // Ix, Iy and Ixy about the centroid, holes subtracted
+    pub fn second_moments(&self) -> (f64, f64, f64) {
+        let first = self.count.running_centroid.unwrap_or((0f64, 0f64));
+        metrics::centroidal_moments(self.area(), first, self.count.running_second_moment)
+    }
+    // Ratio of the area to the area of the convex hull
+    pub fn convexity(&self) -> f64 {
+        let hull_area = self.convex_hull().area();
+        if hull_area == 0f64 { 1f64 } else { self.area() / hull_area }
+    }
+    // Hull of the outer paths, holes can't reach beyond them
+    pub fn convex_hull(&self) -> Path {
+        Path::new(&metrics::convex_hull(self.outer_points()))
+    }
+    // Isoperimetric quotient, one for a circle
+    pub fn compactness(&self) -> f64 {
+        let perimeter = self.perimeter();
+        if perimeter == 0f64 { 0f64 } else { 4.0 * std::f64::consts::PI * self.area() / (perimeter * perimeter) }
+    }
+    pub fn bounding_circle_radius(&self) -> f64 {
+        metrics::bounding_circle_radius(self.outer_points())
+    }
+    // Zero inside the polygon and on its boundary
+    pub fn distance_to_point(&self, point: &Point) -> f64 {
+        if self.covers(point) { return 0f64; }
+        self.paths.iter().map(|path| path.boundary_distance(point)).fold(f64::INFINITY, f64::min)
+    }
+    // Zero for polygons that overlap or touch
+    pub fn distance(&self, other: &Polygon) -> f64 {
+        let overlap = self.paths.iter().flat_map(|path| path.points().iter()).any(|point| other.covers(point)) ||
+            other.paths.iter().flat_map(|path| path.points().iter()).any(|point| self.covers(point));
+        if overlap { return 0f64; }
+        let mut distance = f64::INFINITY;
+        for s1 in self.paths.iter().flat_map(|path| path.segments()) {
+            for s2 in other.paths.iter().flat_map(|path| path.segments()) {
+                distance = distance.min(Straight::distance(&s1, &s2));
+            }
+        }
+        distance
+    }
+    // Same as a position other than out, but exact, Shape::position
+    // treats points closer than half a unit to an edge as lying on it
+    fn covers(&self, point: &Point) -> bool {
+        let (x, y) = (2 * i64::from(point.x().to_int()), 2 * i64::from(point.y().to_int()));
+        let mut inside = false;
+        for path in self.paths.iter() {
+            match path.position_doubled(x, y) {
+                Position::Edge => return true,
+                Position::In => inside = !inside,
+                _ => {}
+            }
+        }
+        inside
+    }
+    fn outer_points(&self) -> impl Iterator<Item = &Point> {
+        self.paths.iter().zip(self.structure.iter())
+            .filter(|(_, record)| record.level == 0)
+            .flat_map(|(path, _)| path.points().iter())
+    }
 
     fn position_at_level(
         &self, point: &Point,
@@ -374,6 +480,312 @@ impl Polygon {
     pub fn structure(&self) -> &Vec<Record> {
         &self.structure
     }
+    pub fn into_paths(self) -> Vec<Path> {
+        self.paths
+    }
+    // Each outer path with its holes as a polygon of its own,
+    // islands within the holes making components of their own
+    pub fn components(&self) -> Vec<Polygon> {
+        (0..self.structure.len())
+            .filter(|index| self.structure[*index].level % 2 != 1)
+            .map(|outer| self.face(outer))
+            .collect()
+    }
+    // Region bounded by the ring and the rings right inside it, as a polygon
+    // of its own. Bounded by a hole, it is a gap between parts
+    fn face(&self, ring: usize) -> Polygon {
+        let rings = self.part(ring);
+        let mut count = PolygonCount::new();
+        let mut paths: Vec<Path> = Vec::with_capacity(rings.len());
+        let mut structure: Vec<Record> = Vec::with_capacity(rings.len());
+        for index in rings.iter() {
+            let record = if *index == ring { Record::new() } else { Record { level: 1, parent: Some(0) } };
+            let path = Polygon::normalize(self.paths[*index].clone(), record.level);
+            count = count.update(&path, record.level);
+            paths.push(path);
+            structure.push(record);
+        }
+        let levels = if rings.len() > 1 { vec![vec![0], (1..rings.len()).collect()] } else { vec![vec![0]] };
+        Polygon { paths, structure, levels, count }
+    }
+    // Removing the rings of a face turns it inside out, a sliver part is
+    // dropped and a sliver gap merged into the part around it. Faces right
+    // inside a removed one are then no longer bounded by it and aren't
+    // removed themselves, a gap in a dropped part stays empty. The rest is
+    // nested anew by geometry. None when no face is a sliver
+    pub(crate) fn remove_slivers<F: Fn(&Polygon) -> bool>(&self, is_sliver: F) -> Result<Option<Polygon>, Error> {
+        let mut removed = vec![false; self.paths.len()];
+        let mut slivers = vec![false; self.paths.len()];
+        let mut rings: Vec<usize> = (0..self.paths.len()).collect();
+        rings.sort_by_key(|ring| self.structure[*ring].level);
+        for ring in rings {
+            if self.structure[ring].parent.is_some_and(|parent| slivers[parent]) { continue; }
+            if is_sliver(&self.face(ring)) {
+                slivers[ring] = true;
+                for index in self.part(ring) {
+                    removed[index] = true;
+                }
+            }
+        }
+        if !removed.contains(&true) { return Ok(None); }
+        let paths: Vec<Path> = self.paths.iter().zip(removed.iter())
+            .filter(|(_, removed)| !**removed)
+            .map(|(path, _)| path.clone())
+            .collect();
+        Polygon::build(paths, &mut GeometricComparator{}).map(Some)
+    }
+    // Inward offset by half the width leaves nothing
+    pub fn is_narrower_than(&self, width: f64) -> bool {
+        let rings: Vec<Vec<(f64, f64)>> = self.paths.iter()
+            .map(|path| path.points().iter().map(|point| (point.float_x(), point.float_y())).collect())
+            .collect();
+        !metrics::reaches_inward(&rings, width / 2.0)
+    }
+    // Mirroring reverses the paths, so that the structure stays valid
+    pub fn transform(&self, transform: &Transform) -> Result<Polygon, Error> {
+        let mut count = PolygonCount::new();
+        let mut paths: Vec<Path> = Vec::with_capacity(self.paths.len());
+        for (path, record) in self.paths.iter().zip(self.structure.iter()) {
+            let path = path.transform(transform)?;
+            count = count.update(&path, record.level);
+            paths.push(path);
+        }
+        Ok(Polygon {
+            paths,
+            structure: self.structure.clone(),
+            levels: self.levels.clone(),
+            count
+        })
+    }
+    // Outer paths are sorted by their starting points and each path
+    // is directly followed by its own children, sorted the same way
+    pub fn canonical(self) -> Polygon {
+        let Polygon { paths, structure: records, levels, count } = self;
+        let paths: Vec<Path> = paths.into_iter().map(Path::canonical).collect();
+        let mut roots: Vec<usize> = Vec::new();
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); paths.len()];
+        for (index, record) in records.iter().enumerate() {
+            match record.parent {
+                Some(parent) => children[parent].push(index),
+                None => roots.push(index)
+            }
+        }
+        let by_path = |a: &usize, b: &usize| Path::canonical_order(&paths[*a], &paths[*b]);
+        roots.sort_by(by_path);
+        for siblings in children.iter_mut() {
+            siblings.sort_by(by_path);
+        }
+        let mut order: Vec<usize> = Vec::with_capacity(paths.len());
+        let mut stack: Vec<usize> = roots.into_iter().rev().collect();
+        while let Some(index) = stack.pop() {
+            order.push(index);
+            stack.extend(children[index].iter().rev());
+        }
+        let mut new_indices = vec![0; paths.len()];
+        for (new_index, old_index) in order.iter().enumerate() {
+            new_indices[*old_index] = new_index;
+        }
+        let structure = order.iter().map(|old_index| {
+            let record = &records[*old_index];
+            Record {
+                level: record.level,
+                parent: record.parent.map(|parent| new_indices[parent])
+            }
+        }).collect();
+        let levels = levels.iter().map(|level| {
+            let mut level: Vec<usize> = level.iter().map(|old_index| new_indices[*old_index]).collect();
+            level.sort();
+            level
+        }).collect();
+        let mut slots: Vec<Option<Path>> = paths.into_iter().map(Some).collect();
+        let paths = order.iter().filter_map(|old_index| slots[*old_index].take()).collect();
+        Polygon { paths, structure, levels, count }
+    }
+    // Rings of one part, an outer path with its holes, touching each other
+    // in a cycle cut the interior apart. Such rings are joined at the touching
+    // points of a spanning tree and split again at the remaining ones.
+    pub(crate) fn untangle(self) -> Result<Polygon, Error> {
+        let mut used = vec![false; self.paths.len()];
+        let mut untangled: Vec<Path> = Vec::new();
+        for outer in 0..self.structure.len() {
+            if self.structure[outer].level % 2 == 1 { continue; }
+            let rings = self.part(outer);
+            let (tree, cycles) = touches(&self.paths, &rings);
+            if cycles.is_empty() { continue; }
+            let mut roots: Vec<usize> = (0..rings.len()).collect();
+            for (a, b, _) in tree.iter() {
+                let (root_a, root_b) = (root(&mut roots, *a), root(&mut roots, *b));
+                roots[root_a] = root_b;
+            }
+            let mut tangled: Vec<usize> = cycles.iter().map(|(ring, _)| root(&mut roots, *ring)).collect();
+            tangled.sort();
+            tangled.dedup();
+            for component in tangled {
+                let members: Vec<usize> = (0..rings.len()).filter(|ring| root(&mut roots, *ring) == component).collect();
+                let candidates: Vec<Point> = members.iter()
+                    .flat_map(|ring| self.paths[rings[*ring]].points().iter().cloned())
+                    .collect();
+                let mut joined: Vec<Option<Path>> = (0..rings.len()).map(|ring| {
+                    if members.contains(&ring) {
+                        Some(self.paths[rings[ring]].with_points_on_edges(&candidates))
+                    } else {
+                        None
+                    }
+                }).collect();
+                let mut groups: Vec<usize> = (0..rings.len()).collect();
+                let mut kept: Vec<Point> = Vec::new();
+                for (a, b, point) in tree.iter().filter(|(a, _, _)| members.contains(a)) {
+                    let (group_a, group_b) = (groups[*a], groups[*b]);
+                    let path = joined[group_a].take().unwrap().joined(&joined[group_b].take().unwrap(), point);
+                    joined[group_a] = Some(path);
+                    for group in groups.iter_mut().filter(|group| **group == group_b) {
+                        *group = group_a;
+                    }
+                    if !cycles.iter().any(|(_, cycle)| cycle == point) {
+                        kept.push(point.clone());
+                    }
+                }
+                // Once the cycles are cut, the joints can be split again
+                for path in joined.into_iter().flatten() {
+                    let paths = path.split_pinches_except(&kept).unwrap_or_else(|| vec![path]);
+                    untangled.extend(paths.into_iter().flat_map(Path::split_pinches));
+                }
+                for ring in members {
+                    used[rings[ring]] = true;
+                }
+            }
+        }
+        if untangled.is_empty() {
+            return Ok(self);
+        }
+        let mut paths: Vec<Path> = self.paths.into_iter().enumerate()
+            .filter_map(|(index, path)| if used[index] { None } else { Some(path) })
+            .collect();
+        paths.append(&mut untangled);
+        Polygon::build(paths, &mut GeometricComparator{})
+    }
+    // A ring must not pass a point twice, and the rings of one part must
+    // not touch each other in a cycle. Returns a point where either happens.
+    pub(crate) fn first_invalid_point(&self) -> Option<Point> {
+        for path in self.paths.iter() {
+            let mut passed: HashSet<&Point> = HashSet::new();
+            if let Some(point) = path.points().iter().find(|point| !passed.insert(point)) {
+                return Some(point.clone());
+            }
+        }
+        (0..self.structure.len())
+            .filter(|outer| self.structure[*outer].level % 2 != 1)
+            .filter_map(|outer| touches(&self.paths, &self.part(outer)).1.into_iter().next())
+            .map(|(_, point)| point)
+            .next()
+    }
+    fn part(&self, outer: usize) -> Vec<usize> {
+        let mut rings: Vec<usize> = vec![outer];
+        rings.extend((0..self.structure.len()).filter(|index| self.structure[*index].parent == Some(outer)));
+        rings
+    }
+}
+// Points of the path lying on the edges of the other one. The points are
+// sorted by x, each edge only looks at those within its horizontal extent
+fn points_on_edges<'a>(path: &'a Path, other: &Path) -> Vec<&'a Point> {
+    let mut sorted: Vec<&Point> = path.points().iter().collect();
+    sorted.sort_by_key(|point| point.x());
+    let mut found: Vec<&Point> = (0..other.points().len())
+        .filter_map(|index| other.segment_at(index))
+        .flat_map(|segment| {
+            let (left, right) = if segment.start.x() < segment.end.x() {
+                (segment.start.x(), segment.end.x())
+            } else {
+                (segment.end.x(), segment.start.x())
+            };
+            let first = sorted.partition_point(|point| point.x() < left);
+            let last = sorted.partition_point(|point| point.x() <= right);
+            sorted[first..last].iter()
+                .filter(move |point| segment.contains(point, &Mode::Closed))
+                .copied()
+                .collect::<Vec<&Point>>()
+        })
+        .collect();
+    // In the order of the path
+    let order: HashMap<&Point, usize> = path.points().iter().enumerate().map(|(index, point)| (point, index)).collect();
+    found.sort_by_key(|point| order[point]);
+    found
+}
+fn touching_points(a: &Path, b: &Path) -> Vec<Point> {
+    let mut seen: HashSet<&Point> = HashSet::new();
+    points_on_edges(a, b).into_iter()
+        .chain(points_on_edges(b, a))
+        .filter(|point| seen.insert(point))
+        .cloned()
+        .collect()
+}
+fn root(roots: &mut [usize], mut index: usize) -> usize {
+    while roots[index] != index {
+        roots[index] = roots[roots[index]];
+        index = roots[index];
+    }
+    index
+}
+// Touches among the given rings, indexed by position in the slice. Rings and
+// touching points form a graph, several rings meeting at one point don't make
+// a cycle. Touches joining the rings into a spanning tree are given as pairs
+// of rings with the point, the others as the ring closing a cycle.
+type Touches = (Vec<(usize, usize, Point)>, Vec<(usize, Point)>);
+fn touches(paths: &[Path], rings: &[usize]) -> Touches {
+    let mut roots: Vec<usize> = (0..rings.len()).collect();
+    let mut points: Vec<(Point, usize)> = Vec::new();
+    let mut indices: HashMap<Point, usize> = HashMap::new();
+    let mut adjacent: HashSet<(usize, usize)> = HashSet::new();
+    let mut tree = Vec::new();
+    let mut cycles = Vec::new();
+    for a in 0..rings.len() {
+        for b in (a + 1)..rings.len() {
+            let (path_a, path_b) = (&paths[rings[a]], &paths[rings[b]]);
+            let collide = match (path_a.bounds(), path_b.bounds()) {
+                (Some(bounds_a), Some(bounds_b)) => Bounds::have_contact(bounds_a, bounds_b),
+                _ => false
+            };
+            if !collide { continue; }
+            for point in touching_points(path_a, path_b) {
+                for ring in &[a, b] {
+                    let index = match indices.get(&point) {
+                        Some(index) => *index,
+                        None => {
+                            indices.insert(point.clone(), points.len());
+                            points.push((point.clone(), *ring));
+                            roots.push(roots.len());
+                            let node = roots.len() - 1;
+                            roots[node] = root(&mut roots, *ring);
+                            adjacent.insert((*ring, points.len() - 1));
+                            continue;
+                        }
+                    };
+                    if !adjacent.insert((*ring, index)) { continue; }
+                    let node = rings.len() + index;
+                    let (root_ring, root_node) = (root(&mut roots, *ring), root(&mut roots, node));
+                    if root_ring == root_node {
+                        cycles.push((*ring, point.clone()));
+                    } else {
+                        roots[root_ring] = root_node;
+                        tree.push((*ring, points[index].1, point.clone()));
+                    }
+                }
+            }
+        }
+    }
+    (tree, cycles)
+}
+impl PartialEq for Polygon {
+    fn eq(&self, other: &Polygon) -> bool {
+        self.paths == other.paths && self.structure == other.structure
+    }
+}
+impl Eq for Polygon {}
+impl Hash for Polygon {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.paths.hash(state);
+        self.structure.hash(state);
+    }
 }
 impl Shape for Polygon {
     fn position(&self, point: &Point) -> Position {
@@ -408,7 +820,7 @@ impl Shape for Polygon {
 
 #[cfg(test)]
 mod test {
-    use crate::primitives::{Sector, Mode};
+    use crate::primitives::Mode;
     use super::Path;
 //    use crate::position_count_algorithm::position_count::PositionCount;
     use super::Shape;
@@ -680,30 +1092,225 @@ mod test {
         assert_eq!(poly.count.centroid().unwrap(), Point::new(9, 4).expect("!"));
         assert_eq!(poly.count.area(), 80f64);
 
-        assert!(!poly.contains(&Point::new(-1, 1).expect("!"), &Mode::Closed));
-        assert!(poly.contains(&Point::new(0, 1).expect("!"), &Mode::Closed));
-        assert!(poly.contains(&Point::new(1, 1).expect("!"), &Mode::Closed));
-        assert!(poly.contains(&Point::new(2, 1).expect("!"), &Mode::Closed));
-        assert!(!poly.contains(&Point::new(3, 1).expect("!"), &Mode::Closed));
-        assert!(poly.contains(&Point::new(4, 1).expect("!"), &Mode::Closed));
-        assert!(poly.contains(&Point::new(5, 1).expect("!"), &Mode::Closed));
-        assert!(poly.contains(&Point::new(6, 1).expect("!"), &Mode::Closed));
-        assert!(poly.contains(&Point::new(7, 1).expect("!"), &Mode::Closed));
-        assert!(poly.contains(&Point::new(8, 1).expect("!"), &Mode::Closed));
-        assert!(!poly.contains(&Point::new(9, 1).expect("!"), &Mode::Closed));
-        assert!(poly.contains(&Point::new(10, 1).expect("!"), &Mode::Closed));
-        assert!(!poly.contains(&Point::new(11, 1).expect("!"), &Mode::Closed));
-        assert!(poly.contains(&Point::new(12, 1).expect("!"), &Mode::Closed));
-        assert!(!poly.contains(&Point::new(13, 1).expect("!"), &Mode::Closed));
-        assert!(poly.contains(&Point::new(14, 1).expect("!"), &Mode::Closed));
-        assert!(poly.contains(&Point::new(15, 1).expect("!"), &Mode::Closed));
-        assert!(poly.contains(&Point::new(16, 1).expect("!"), &Mode::Closed));
-        assert!(poly.contains(&Point::new(17, 1).expect("!"), &Mode::Closed));
-        assert!(poly.contains(&Point::new(18, 1).expect("!"), &Mode::Closed));
-        assert!(!poly.contains(&Point::new(19, 1).expect("!"), &Mode::Closed));
-        assert!(poly.contains(&Point::new(20, 1).expect("!"), &Mode::Closed));
-        assert!(poly.contains(&Point::new(21, 1).expect("!"), &Mode::Closed));
-        assert!(poly.contains(&Point::new(22, 1).expect("!"), &Mode::Closed));
-        assert!(!poly.contains(&Point::new(23, 1).expect("!"), &Mode::Closed));
-    }
-}
\ No newline at end of file
+        assert!(!poly.contains(&Point::new(-1, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(poly.contains(&Point::new(0, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(poly.contains(&Point::new(1, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(poly.contains(&Point::new(2, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(!poly.contains(&Point::new(3, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(poly.contains(&Point::new(4, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(poly.contains(&Point::new(5, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(poly.contains(&Point::new(6, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(poly.contains(&Point::new(7, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(poly.contains(&Point::new(8, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(!poly.contains(&Point::new(9, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(poly.contains(&Point::new(10, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(!poly.contains(&Point::new(11, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(poly.contains(&Point::new(12, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(!poly.contains(&Point::new(13, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(poly.contains(&Point::new(14, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(poly.contains(&Point::new(15, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(poly.contains(&Point::new(16, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(!poly.contains(&Point::new(17, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(poly.contains(&Point::new(18, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(!poly.contains(&Point::new(19, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(poly.contains(&Point::new(20, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(poly.contains(&Point::new(21, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(poly.contains(&Point::new(22, 1).expect("!"), &Mode::Closed).unwrap());
+        assert!(!poly.contains(&Point::new(23, 1).expect("!"), &Mode::Closed).unwrap());
+    }
+    #[test]
+    fn canonical_test() {
+        fn square(left: i32, top: i32, side: i32) -> Path {
+            Path::new(&vec![
+                Point::new(left + side, top).expect("!"),
+                Point::new(left + side, top + side).expect("!"),
+                Point::new(left, top + side).expect("!"),
+                Point::new(left, top).expect("!")
+            ])
+        }
+        let island = square(3, 3, 2);
+        let right = square(10, 0, 4);
+        let hole = square(2, 2, 4);
+        let left = square(0, 0, 8);
+        let other_hole = square(11, 1, 2);
+        let paths = vec![island, right, hole, left, other_hole];
+        let mut comp = NaiveComparator{};
+        let poly = Polygon::build(paths.clone(), &mut comp).unwrap().canonical();
+        let starts: Vec<Point> = poly.paths().iter().map(|path| path.points()[0].clone()).collect();
+        assert_eq!(starts, vec![
+            Point::new(0, 0).expect("!"),
+            Point::new(2, 2).expect("!"),
+            Point::new(3, 3).expect("!"),
+            Point::new(10, 0).expect("!"),
+            Point::new(11, 1).expect("!")
+        ]);
+        let parents: Vec<Option<usize>> = poly.structure().iter().map(|record| record.parent()).collect();
+        assert_eq!(parents, vec![None, Some(0), Some(1), None, Some(3)]);
+        assert_eq!(poly.levels, vec![vec![0, 3], vec![1, 4], vec![2]]);
+        assert_eq!(poly.paths()[0].direction(), PathDirection::Clockwise);
+        assert_eq!(poly.paths()[1].direction(), PathDirection::Counterclockwise);
+        assert_eq!(poly.paths()[2].direction(), PathDirection::Clockwise);
+
+        let mut reversed = paths;
+        reversed.reverse();
+        let other = Polygon::build(reversed, &mut comp).unwrap().canonical();
+        assert!(poly == other);
+    }
+    #[test]
+    fn first_invalid_point_test() {
+        let path = |points: Vec<(i32, i32)>| Path::new(
+            &points.into_iter().map(|(x, y)| Point::new(x, y).expect("!")).collect()
+        );
+        let outer = path(vec![(0, 0), (10, 0), (10, 10), (0, 10)]);
+        let mut comp = NaiveComparator{};
+        let touching = path(vec![(0, 5), (5, 2), (5, 8)]);
+        let poly = Polygon::build(vec![outer.clone(), touching], &mut comp).unwrap();
+        assert_eq!(poly.first_invalid_point(), None);
+        let cutting = path(vec![(0, 5), (5, 2), (10, 5), (5, 8)]);
+        let poly = Polygon::build(vec![outer.clone(), cutting], &mut comp).unwrap();
+        let point = poly.first_invalid_point().unwrap();
+        assert!(point == Point::new(0, 5).expect("!") || point == Point::new(10, 5).expect("!"));
+        let pinched = path(vec![(0, 0), (4, 4), (8, 0), (8, 8), (4, 4), (0, 8)]);
+        let poly = unsafe { Polygon::trivial(pinched) };
+        assert_eq!(poly.first_invalid_point(), Some(Point::new(4, 4).expect("!")));
+    }
+    #[test]
+    fn first_invalid_point_long_test() {
+        let path = |points: Vec<(i32, i32)>| Path::new(
+            &points.into_iter().map(|(x, y)| Point::new(x, y).expect("!")).collect()
+        );
+        let side = 50000;
+        let mut outer: Vec<(i32, i32)> = (0..side).map(|x| (x, 0)).collect();
+        outer.extend(vec![(side, 0), (side, 10), (0, 10)]);
+        let mut hole: Vec<(i32, i32)> = (1..side).map(|x| (x, 5)).collect();
+        hole.extend(vec![(side - 1, 8), (1, 8)]);
+        // Touching the outer path once at its bottom
+        hole.insert(side as usize / 2, (side / 2, 0));
+        let mut comp = super::GeometricComparator{};
+        let poly = Polygon::build(vec![path(outer), path(hole).reverse()], &mut comp).unwrap();
+        assert_eq!(poly.first_invalid_point(), None);
+    }
+    #[test]
+    fn untangle_test() {
+        let path = |points: Vec<(i32, i32)>| Path::new(
+            &points.into_iter().map(|(x, y)| Point::new(x, y).expect("!")).collect()
+        );
+        let outer = path(vec![(0, 0), (10, 0), (10, 10), (0, 10)]);
+        let cutting = path(vec![(0, 5), (5, 2), (10, 5), (5, 8)]);
+        let mut comp = NaiveComparator{};
+        let poly = Polygon::build(vec![outer, cutting], &mut comp).unwrap().untangle().unwrap();
+        assert_eq!(poly.paths().len(), 2);
+        assert_eq!(poly.area(), 70.0);
+        assert!(poly.structure().iter().all(|record| record.parent().is_none()));
+        assert_eq!(poly.first_invalid_point(), None);
+    }
+    #[test]
+    fn components_test() {
+        let path = |points: Vec<(i32, i32)>| Path::new(
+            &points.into_iter().map(|(x, y)| Point::new(x, y).expect("!")).collect()
+        );
+        let outer = path(vec![(0, 0), (10, 0), (10, 10), (0, 10)]);
+        let hole = path(vec![(2, 2), (8, 2), (8, 8), (2, 8)]);
+        let island = path(vec![(4, 4), (6, 4), (6, 6), (4, 6)]);
+        let apart = path(vec![(20, 0), (22, 0), (22, 2), (20, 2)]);
+        let mut comp = NaiveComparator{};
+        let poly = Polygon::build(vec![island, apart, outer, hole], &mut comp).unwrap();
+        let components = poly.components();
+        let areas: Vec<f64> = components.iter().map(|component| component.area()).collect();
+        assert_eq!(areas.iter().sum::<f64>(), poly.area());
+        assert_eq!(components.len(), 3);
+        let framed = components.iter().find(|component| component.area() == 64.0).unwrap();
+        assert_eq!(framed.paths().len(), 2);
+        assert_eq!(framed.structure()[1].parent(), Some(0));
+        assert_eq!(framed.paths()[1].direction(), PathDirection::Counterclockwise);
+        assert!(components.iter().any(|component| component.area() == 4.0 && component.bounds().unwrap().left().to_int() == 4));
+    }
+    #[test]
+    fn remove_slivers_test() {
+        let path = |points: Vec<(i32, i32)>| Path::new(
+            &points.into_iter().map(|(x, y)| Point::new(x, y).expect("!")).collect()
+        );
+        let outer = path(vec![(0, 0), (10, 0), (10, 10), (0, 10)]);
+        let hole = path(vec![(1, 1), (9, 1), (9, 9), (1, 9)]);
+        let island = path(vec![(2, 2), (8, 2), (8, 8), (2, 8)]);
+        let mut comp = NaiveComparator{};
+        let poly = Polygon::build(vec![outer, hole, island], &mut comp).unwrap();
+        assert!(poly.remove_slivers(|face| face.area() < 1.0).unwrap().is_none());
+        // The frame is dropped, the gap around the island with it
+        let thick = poly.remove_slivers(|face| face.is_narrower_than(1.5)).unwrap().unwrap();
+        assert_eq!(thick.paths().len(), 1);
+        assert_eq!(thick.area(), 36.0);
+        let small = poly.remove_slivers(|face| face.area() < 30.0).unwrap().unwrap();
+        assert_eq!(small.paths().len(), 1);
+        assert_eq!(small.area(), 100.0);
+        // A tiny gap in a narrow annulus isn't filled as the annulus is dropped
+        let outer = path(vec![(0, 0), (20, 0), (20, 20), (0, 20)]);
+        let hole = path(vec![(4, 4), (16, 4), (16, 16), (4, 16)]);
+        let gap = path(vec![(1, 1), (2, 1), (2, 2), (1, 2)]);
+        let island = path(vec![(6, 6), (14, 6), (14, 14), (6, 14)]);
+        let annulus = Polygon::build(vec![outer, hole, gap, island], &mut comp).unwrap();
+        let kept = annulus.remove_slivers(|face| face.is_narrower_than(5.0)).unwrap().unwrap();
+        assert_eq!(kept.paths().len(), 1);
+        assert_eq!(kept.area(), 64.0);
+        let strip = Polygon::build(vec![path(vec![(0, 0), (100000, 0), (100000, 2), (0, 2)])], &mut comp).unwrap();
+        assert!(strip.is_narrower_than(2.001));
+        assert!(!strip.is_narrower_than(1.9));
+    }
+    #[test]
+    fn metrics_test() {
+        let path = |points: Vec<(i32, i32)>| Path::new(
+            &points.into_iter().map(|(x, y)| Point::new(x, y).expect("!")).collect()
+        );
+        let outer = path(vec![(0, 0), (10, 0), (10, 10), (0, 10)]);
+        let hole = path(vec![(4, 4), (6, 4), (6, 6), (4, 6)]);
+        let mut comp = NaiveComparator{};
+        let poly = Polygon::build(vec![hole, outer], &mut comp).unwrap();
+        assert_eq!(poly.perimeter(), 48.0);
+        let (ix, iy, ixy) = poly.second_moments();
+        assert!((ix - 832.0).abs() < 1e-9 && (iy - 832.0).abs() < 1e-9 && ixy.abs() < 1e-9);
+        assert_eq!(poly.convexity(), 0.96);
+        assert!((poly.bounding_circle_radius() - 50f64.sqrt()).abs() < 1e-9);
+    }
+    #[test]
+    fn convex_hull_test() {
+        let path = |points: Vec<(i32, i32)>| Path::new(
+            &points.into_iter().map(|(x, y)| Point::new(x, y).expect("!")).collect()
+        );
+        let left = path(vec![(0, 0), (4, 0), (4, 4), (0, 4)]);
+        let hole = path(vec![(1, 1), (3, 1), (3, 3), (1, 3)]);
+        let right = path(vec![(6, 2), (8, 2), (8, 6), (6, 6)]);
+        let mut comp = NaiveComparator{};
+        let poly = Polygon::build(vec![left, hole, right], &mut comp).unwrap();
+        let hull = poly.convex_hull();
+        assert!(hull == path(vec![(0, 0), (4, 0), (8, 2), (8, 6), (6, 6), (0, 4)]));
+        assert_eq!(hull.area(), 38.0);
+    }
+    #[test]
+    fn distance_test() {
+        let path = |points: Vec<(i32, i32)>| Path::new(
+            &points.into_iter().map(|(x, y)| Point::new(x, y).expect("!")).collect()
+        );
+        let mut comp = NaiveComparator{};
+        let outer = path(vec![(0, 0), (10, 0), (10, 10), (0, 10)]);
+        let hole = path(vec![(2, 2), (8, 2), (8, 8), (2, 8)]);
+        let ring = Polygon::build(vec![outer, hole], &mut comp).unwrap();
+        assert_eq!(ring.distance_to_point(&Point::new(1, 5).expect("!")), 0.0);
+        assert_eq!(ring.distance_to_point(&Point::new(10, 5).expect("!")), 0.0);
+        assert_eq!(ring.distance_to_point(&Point::new(5, 4).expect("!")), 2.0);
+        assert_eq!(ring.distance_to_point(&Point::new(13, 14).expect("!")), 5.0);
+        let inside = Polygon::build(vec![path(vec![(4, 4), (6, 4), (6, 6), (4, 6)])], &mut comp).unwrap();
+        assert_eq!(ring.distance(&inside), 2.0);
+        assert_eq!(inside.distance(&ring), 2.0);
+        let crossing = Polygon::build(vec![path(vec![(9, 4), (12, 4), (12, 6), (9, 6)])], &mut comp).unwrap();
+        assert_eq!(ring.distance(&crossing), 0.0);
+        let touching = Polygon::build(vec![path(vec![(10, 10), (12, 10), (12, 12)])], &mut comp).unwrap();
+        assert_eq!(ring.distance(&touching), 0.0);
+        let apart = Polygon::build(vec![path(vec![(13, 14), (15, 14), (15, 16)])], &mut comp).unwrap();
+        assert_eq!(ring.distance(&apart), 5.0);
+        let near = Polygon::build(vec![path(vec![(1, 0), (100, 1), (100, -5)])], &mut comp).unwrap();
+        let corner = Polygon::build(vec![path(vec![(-5, -5), (1, -5), (1, 0)])], &mut comp).unwrap();
+        assert_eq!(near.distance(&corner), 0.0);
+        let point = Point::new(99, 1).expect("!");
+        assert!(near.distance_to_point(&point) > 0.0 && near.distance_to_point(&point) < 0.5);
+    }
+}
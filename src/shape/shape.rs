@@ -1,27 +1,43 @@
-use crate::primitives::{Point, Position, Mode, Bounds, Sector};
+use crate::primitives::{AbstractPoint, Point, Position, Mode, Bounds, Sector};
+use crate::error::Error;
 use super::path::Path;
 
 pub trait Shape {
     fn position(&self, point: &Point) -> Position;
     fn bounds(&self) -> Option<&Bounds>;
     fn paths(&self) -> Vec<&Path>;
-}
-impl <T: Shape> Sector for T {
-    fn contains(&self, point: &Point, mode: &Mode) -> bool {
+    fn contains(&self, point: &Point, mode: &Mode) -> Result<bool, Error> {
         match self.bounds() {
-            None => return false,
+            None => return Ok(false),
             Some(ref bounds) => {
                 if !bounds.contains(point, mode) {
-                    return false
+                    return Ok(false)
                 }
             }
         }
-        let position = self.position(point);
-        match position {
-            Position::In => true,
-            Position::Out => false,
-            Position::Edge => *mode == Mode::Closed,
-            Position::Unknown => panic!("Definite position expected")
+        match self.position(point) {
+            Position::In => Ok(true),
+            Position::Out => Ok(false),
+            Position::Edge => Ok(*mode == Mode::Closed),
+            Position::Unknown => Err(Error::FatalError(format!("No definite position for {}", point.inspect())))
         }
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn unknown_position_test() {
+    struct Undecided { path: Path }
+    impl Shape for Undecided {
+        fn position(&self, _point: &Point) -> Position { Position::Unknown }
+        fn bounds(&self) -> Option<&Bounds> { self.path.bounds() }
+        fn paths(&self) -> Vec<&Path> { vec![&self.path] }
+    }
+    let points = [(0, 0), (4, 0), (4, 4), (0, 4)].iter().map(|&(x, y)| Point::new(x, y).unwrap()).collect();
+    let path = Path::new(&points);
+    let undecided = Undecided { path };
+    assert!(!undecided.contains(&Point::new(9, 9).unwrap(), &Mode::Closed).unwrap());
+    match undecided.contains(&Point::new(2, 2).unwrap(), &Mode::Closed) {
+        Err(Error::FatalError(_)) => (),
+        other => panic!("Expected FatalError, got {:?}", other)
+    }
+}
@@ -0,0 +1,157 @@
+use crate::primitives::Point;
+use crate::shape::Path;
+use crate::shape::path::coordinates;
+use crate::units::Coordinate;
+use crate::Error;
+use std::collections::{BTreeMap, BTreeSet};
+use std::cmp::Ordering;
+
+// Hot pixels are squares twice the tolerance wide, centred at multiples
+// of their width, each containing a vertex of the input. They are half-open,
+// containing their left and bottom sides, as the vertices are assigned to them
+struct HotPixels {
+    tolerance: i64,
+    centres: BTreeMap<i64, BTreeSet<i64>>
+}
+// Half-open pixels are tested as closed ones shrunk by a fraction of a unit,
+// small enough not to matter for segments between points of the grid
+const SCALE: i128 = 1 << 30;
+// Numerator and positive denominator
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Fraction(i64, i64);
+impl PartialOrd for Fraction {
+    fn partial_cmp(&self, other: &Fraction) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Fraction {
+    fn cmp(&self, other: &Fraction) -> Ordering {
+        (i128::from(self.0) * i128::from(other.1)).cmp(&(i128::from(other.0) * i128::from(self.1)))
+    }
+}
+impl HotPixels {
+    fn new(paths: &[Path], tolerance: i64) -> HotPixels {
+        let mut pixels = HotPixels { tolerance, centres: BTreeMap::new() };
+        for point in paths.iter().flat_map(|path| path.points().iter()) {
+            let centre = pixels.centre(coordinates(point));
+            pixels.centres.entry(centre.0).or_default().insert(centre.1);
+        }
+        pixels
+    }
+    fn centre(&self, point: (i64, i64)) -> (i64, i64) {
+        let width = 2 * self.tolerance;
+        let snap = |value: i64| (value + self.tolerance).div_euclid(width) * width;
+        (snap(point.0), snap(point.1))
+    }
+    fn passes(&self, start: (i64, i64), end: (i64, i64), centre: (i64, i64)) -> bool {
+        let tolerance = i128::from(self.tolerance);
+        let low = (SCALE * (i128::from(centre.0) - tolerance), SCALE * (i128::from(centre.1) - tolerance));
+        let high = (SCALE * (i128::from(centre.0) + tolerance) - 1, SCALE * (i128::from(centre.1) + tolerance) - 1);
+        let (start, end) = (
+            (SCALE * i128::from(start.0), SCALE * i128::from(start.1)),
+            (SCALE * i128::from(end.0), SCALE * i128::from(end.1))
+        );
+        if start.0.max(end.0) < low.0 || start.0.min(end.0) > high.0 { return false; }
+        if start.1.max(end.1) < low.1 || start.1.min(end.1) > high.1 { return false; }
+        let normal = ((start.1 - end.1) / SCALE, (end.0 - start.0) / SCALE);
+        let distance = normal.0 * (low.0 + high.0 - 2 * start.0) + normal.1 * (low.1 + high.1 - 2 * start.1);
+        distance.abs() <= normal.0.abs() * (high.0 - low.0) + normal.1.abs() * (high.1 - low.1)
+    }
+    // Parameters of the points where the segment enters and leaves the pixel,
+    // kept as exact fractions, a segment may touch a pixel at a single point
+    // right where it enters the next one
+    fn span(&self, start: (i64, i64), end: (i64, i64), centre: (i64, i64)) -> (Fraction, Fraction) {
+        let mut span = (Fraction(0, 1), Fraction(1, 1));
+        for (start, end, centre) in [(start.0, end.0, centre.0), (start.1, end.1, centre.1)] {
+            let direction = end - start;
+            if direction == 0 { continue; }
+            let sign = direction.signum();
+            let low = Fraction(sign * (centre - self.tolerance - start), direction.abs());
+            let high = Fraction(sign * (centre + self.tolerance - start), direction.abs());
+            let (enter, leave) = if low < high { (low, high) } else { (high, low) };
+            span = (span.0.max(enter), span.1.min(leave));
+        }
+        span
+    }
+    // Centres of the pixels the segment passes through, in the order along it
+    fn passed(&self, start: (i64, i64), end: (i64, i64)) -> Vec<(i64, i64)> {
+        let tolerance = self.tolerance;
+        let mut passed: Vec<((Fraction, Fraction), (i64, i64))> = Vec::new();
+        let columns = self.centres.range(start.0.min(end.0) - tolerance..=start.0.max(end.0) + tolerance);
+        for (x, ys) in columns {
+            for y in ys.range(start.1.min(end.1) - tolerance..=start.1.max(end.1) + tolerance) {
+                let centre = (*x, *y);
+                if self.passes(start, end, centre) {
+                    passed.push((self.span(start, end, centre), centre));
+                }
+            }
+        }
+        passed.sort_by_key(|(span, _)| *span);
+        passed.into_iter().map(|(_, centre)| centre).collect()
+    }
+    // The segment goes through the centres of the pixels it passes, then
+    // as in iterated snap rounding, so do the new segments until none
+    // passes any pixels other than those at its ends
+    fn route(&self, start: (i64, i64), end: (i64, i64), route: &mut Vec<(i64, i64)>) {
+        let centres = self.passed(start, end);
+        let mut pending: Vec<((i64, i64), (i64, i64))> = centres.windows(2).rev().map(|pair| (pair[0], pair[1])).collect();
+        while let Some((start, end)) = pending.pop() {
+            let passed = self.passed(start, end);
+            if passed.len() > 2 {
+                pending.extend(passed.windows(2).rev().map(|pair| (pair[0], pair[1])));
+            } else {
+                route.push(end);
+            }
+        }
+    }
+}
+// Paths made of the centres of hot pixels, possibly overlapping
+// each other or themselves but never crossing. Centres of the outermost
+// pixels may lie past the bounds, they are moved onto them, by less than
+// the tolerance and keeping their order
+pub fn snap_round(paths: &[Path], tolerance: i64) -> Result<Vec<Path>, Error> {
+    let pixels = HotPixels::new(paths, tolerance);
+    let mut snapped: Vec<Path> = Vec::with_capacity(paths.len());
+    for path in paths {
+        let points: Vec<(i64, i64)> = path.points().iter().map(coordinates).collect();
+        let mut route: Vec<(i64, i64)> = Vec::with_capacity(points.len());
+        for (index, start) in points.iter().enumerate() {
+            pixels.route(*start, points[(index + 1) % points.len()], &mut route);
+        }
+        route.dedup();
+        while route.len() > 1 && route.first() == route.last() {
+            route.pop();
+        }
+        if route.len() < 3 { continue; }
+        let points = route.into_iter()
+            .map(|(x, y)| Point::new(clamp(x), clamp(y)))
+            .collect::<Result<Vec<Point>, Error>>()?;
+        snapped.push(Path::new(&points));
+    }
+    Ok(snapped)
+}
+fn clamp(value: i64) -> i32 {
+    value.clamp(i64::from(Coordinate::MIN), i64::from(Coordinate::MAX)) as i32
+}
+
+#[cfg(test)]
+mod test {
+    use super::HotPixels;
+    use crate::shape::Path;
+    use crate::primitives::Point;
+
+    #[test]
+    fn hot_pixels_test() {
+        let path = Path::new(&vec![Point::new(0, 0).unwrap(), Point::new(9, 1).unwrap(), Point::new(21, 0).unwrap()]);
+        let pixels = HotPixels::new(&[path], 2);
+        assert_eq!(pixels.centre((9, 1)), (8, 0));
+        assert_eq!(pixels.centre((-2, 2)), (0, 4));
+        assert_eq!(pixels.passed((0, 0), (20, 0)), vec![(0, 0), (8, 0), (20, 0)]);
+        assert_eq!(pixels.passed((0, -2), (20, -2)), vec![(0, 0), (8, 0), (20, 0)]);
+        assert_eq!(pixels.passed((0, 2), (20, 2)), vec![]);
+        assert_eq!(pixels.passed((0, 4), (16, 0)), vec![(8, 0)]);
+        let mut route = Vec::new();
+        pixels.route((1, 1), (19, -1), &mut route);
+        assert_eq!(route, vec![(8, 0), (20, 0)]);
+    }
+}
@@ -61,6 +61,18 @@ fn null_segment_polygon_test() {
     assert_eq!(g1.num_edges(), 4);
 }
 
+#[test]
+fn large_coordinates_centroid_test() {
+    let points: Vec<Point> = vec![
+        (8245260, -5538793), (12533244, -2219398), (15564914, 8059594),
+        (502773, -2271175), (-3098498, -8545511), (-15110131, -1066446)
+    ].iter().map(|(x, y)| Point::new(*x, *y).unwrap()).collect();
+    let path = Path::new(&points);
+    let flat = unsafe { Polygon::flat(vec![path.clone()]) };
+    assert!(flat.is_ok());
+    let normalized = Snipper::normalize(vec![path]).unwrap().polygon();
+    assert!(normalized.is_ok());
+}
 #[test]
 fn first_random_fiasco_test() {
     let r = Snipper::union(
@@ -516,7 +528,7 @@ fn null_edges_perform1_test() {
         vec![0, 3, 1],
         vec![2, 4, 6, 10, 9],
     ];
-    test_operation(operands, &Operation::UNION, &exp);
+    test_raw_operation(operands, &Operation::UNION, &exp);
 }
 
 #[test]
@@ -601,7 +613,7 @@ fn neighbour_polygons_perform_test() {
         vec![0, 3, 9, 12, 13, 14, 6, 2, 1],
         vec![8, 11, 10, 7]
     ];
-    test_operation(operands.iter().cloned().collect(), &Operation::UNION, &exp);
+    test_raw_operation(operands.iter().cloned().collect(), &Operation::UNION, &exp);
     let exp = vec![];
     test_operation(operands.iter().cloned().collect(), &Operation::INTERSECTION, &exp);
 }
@@ -622,7 +634,7 @@ fn xor_perform_test() {
     // #0[0, -10], #1[0, 0], #2[4, -8], #3[5, -10], #4[10, -20],
     // #5[10, -5], #6[15, -10], #7[16, -8], #8[20, -10], #9[20, 0]
     let exp = vec![vec![0, 3, 4, 6, 8, 7, 6, 3, 2], vec![1, 2, 5, 7, 9, 5]];
-    test_operation(operands, &Operation::XOR, &exp);
+    test_raw_operation(operands, &Operation::XOR, &exp);
 
     let mut operands: Vec<Polygon> = Vec::new();
     operands.push(get_complex_polygon("star"));
@@ -636,7 +648,7 @@ fn xor_perform_test() {
         vec![0, 3, 4, 6, 8, 9, 12, 14, 9, 6, 3, 1],
         vec![2, 5, 7, 11, 13, 12, 11, 8, 5, 4],
         vec![3, 9, 10]];
-    test_operation(operands, &Operation::XOR, &exp);
+    test_raw_operation(operands, &Operation::XOR, &exp);
 
 }
 #[test]
@@ -659,7 +671,7 @@ fn combination_perform_test() {
     let exp = vec![
         vec![0, 2, 5, 6, 7, 3, 4]
     ];
-    test_operation(operands, &Operation::XOR, &exp);
+    test_raw_operation(operands, &Operation::XOR, &exp);
 
     let mut operands: Vec<Polygon> = Vec::new();
     operands.push(get_complex_polygon("bow"));
@@ -686,7 +698,7 @@ fn perform_operation_test() {
     test_operation(operands.iter().cloned().collect(), &Operation::UNION, &exp);
 
     let exp = vec![vec![2, 4, 7, 5]];
-    test_operation(operands.iter().cloned().collect(), &Operation::INTERSECTION, &exp);
+    test_raw_operation(operands.iter().cloned().collect(), &Operation::INTERSECTION, &exp);
 
     let exp = vec![
         vec![0, 3, 8, 7, 4, 2]
@@ -699,7 +711,7 @@ fn perform_operation_test() {
 
     // ALL: #0[-5, 1], #1[-2, 3], #2[-1, -5], #3[0, 0], #4[1, 5], #5[3, 2], #6[5, -1]
     let exp = vec![vec![0, 2, 6, 5, 4, 1]];
-    test_operation(operands.iter().cloned().collect(), &Operation::UNION, &exp);
+    test_raw_operation(operands.iter().cloned().collect(), &Operation::UNION, &exp);
     let exp = vec![vec![1, 3, 5, 4]];
     test_operation(operands.iter().cloned().collect(), &Operation::INTERSECTION, &exp);
     let exp = vec![];
@@ -729,7 +741,7 @@ fn perform_operation_test() {
         vec![0, 1, 7, 6, 4],
         vec![2, 4, 5]
     ];
-    test_operation(operands.iter().cloned().collect(), &Operation::UNION, &exp);
+    test_raw_operation(operands.iter().cloned().collect(), &Operation::UNION, &exp);
     let exp = vec![vec![3, 6, 4]];
     test_operation(operands.iter().cloned().collect(), &Operation::INTERSECTION, &exp);
     let exp = vec![vec![2, 4, 5]];
@@ -744,7 +756,7 @@ fn perform_difference_test() {
     operands.push(get_complex_polygon("bow"));
     // ALL: #0[-5, 1], #1[-2, 3], #2[-1, -5], #3[0, -3], #4[0, 0], #5[0, 3], #6[1, 5], #7[2, -3], #8[5, -1]
     let exp = vec![vec![0, 2, 7, 8, 6, 1, 5, 4, 1], vec![4, 7, 3]];
-    test_operation(operands.iter().cloned().collect(), &Operation::DIFFERENCE, &exp);
+    test_raw_operation(operands.iter().cloned().collect(), &Operation::DIFFERENCE, &exp);
 }
 #[test]
 fn super_complex_a_test() {
@@ -769,7 +781,7 @@ fn hole_test() {
     // #3[0, -3], #4[0, 0], #5[0, 3],
     // #6[1, 5], #7[2, -3], #8[5, -1]
     let exp = vec![vec![0, 2, 7, 8, 6, 1, 5, 4, 1], vec![4, 7, 3]];
-    test_operation(operands, &Operation::DIFFERENCE, &exp);
+    test_raw_operation(operands, &Operation::DIFFERENCE, &exp);
 }
 
 #[test]
@@ -779,7 +791,7 @@ fn touch_test() {
     operands.push(get_complex_polygon("big"));
     // ALL: #0[-5, 1], #1[-2, 3], #2[-1, -5], #3[0, 0], #4[1, 5], #5[3, 2], #6[5, -1]
     let exp = vec![vec![0, 2, 6, 5, 4, 1]];
-    test_operation(operands, &Operation::UNION, &exp);
+    test_raw_operation(operands, &Operation::UNION, &exp);
 
 }
 #[test]
@@ -798,7 +810,7 @@ fn drawing_algorithm_end_to_end_test() {
         vec![4, 9, 12, 10],
     ];
     let structure = vec![None, Some(0), None];
-    test_operation_and_structure(
+    test_raw_operation_and_structure(
         operands,
         &Operation::XOR,
         &exp,
@@ -1066,12 +1066,7 @@ pub fn all_graph_points(edges: Vec<Edge>) -> Vec<Point> {
     println!("ALL: {}", strings.join(", "));
     all
 }
-pub fn test_operation_and_structure(
-    operands: Vec<Polygon>,
-    operation: &Operation,
-    expected: &Vec<Vec<usize>>,
-    structure: Option<&Vec<Option<usize>>>
-) {
+fn operation_result(operands: Vec<Polygon>, operation: &Operation) -> (Solution, Vec<Point>) {
     assert_eq!(operands.len(), 2);
     let edges = IntersectionAlgorithm::perform(operands[0].clone(), operands[1].clone()).unwrap();
     let all = all_graph_points(edges);
@@ -1084,11 +1079,33 @@ pub fn test_operation_and_structure(
         },
         _ => panic!("Unknown operation")
     };
-    test_result_and_structure(result.unwrap(), &all, expected, structure);
+    (result.unwrap(), all)
+}
+pub fn test_operation_and_structure(
+    operands: Vec<Polygon>,
+    operation: &Operation,
+    expected: &Vec<Vec<usize>>,
+    structure: Option<&Vec<Option<usize>>>
+) {
+    let (result, all) = operation_result(operands, operation);
+    test_result_and_structure(result, &all, expected, structure);
 }
 pub fn test_operation(operands: Vec<Polygon>, operation: &Operation, expected: &Vec<Vec<usize>>) {
     test_operation_and_structure(operands, operation, expected, None);
 }
+// Paths as drawn, with the collinear points where split edges meet again
+pub fn test_raw_operation_and_structure(
+    operands: Vec<Polygon>,
+    operation: &Operation,
+    expected: &Vec<Vec<usize>>,
+    structure: Option<&Vec<Option<usize>>>
+) {
+    let (result, all) = operation_result(operands, operation);
+    test_result_and_structure(result.without_cleanup(), &all, expected, structure);
+}
+pub fn test_raw_operation(operands: Vec<Polygon>, operation: &Operation, expected: &Vec<Vec<usize>>) {
+    test_raw_operation_and_structure(operands, operation, expected, None);
+}
 pub fn test_result_and_structure(
     solution: Solution,
     points: &Vec<Point>,
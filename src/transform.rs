@@ -0,0 +1,95 @@
+use crate::primitives::Point;
+use crate::Error;
+
+// Affine map x' = a * x + b * y + c, y' = d * x + e * y + f,
+// built up by chaining, each step applied after the previous ones
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transform {
+    a: f64, b: f64, c: f64,
+    d: f64, e: f64, f: f64
+}
+impl Transform {
+    pub fn identity() -> Transform {
+        Transform { a: 1.0, b: 0.0, c: 0.0, d: 0.0, e: 1.0, f: 0.0 }
+    }
+    pub fn translate(self, dx: f64, dy: f64) -> Transform {
+        self.then(&Transform { a: 1.0, b: 0.0, c: dx, d: 0.0, e: 1.0, f: dy })
+    }
+    // Counterclockwise with the y axis pointing up, in radians
+    pub fn rotate(self, angle: f64) -> Transform {
+        let (sin, cos) = angle.sin_cos();
+        self.then(&Transform { a: cos, b: -sin, c: 0.0, d: sin, e: cos, f: 0.0 })
+    }
+    pub fn rotate_around(self, angle: f64, center: &Point) -> Transform {
+        let (x, y) = (center.float_x(), center.float_y());
+        self.translate(-x, -y).rotate(angle).translate(x, y)
+    }
+    pub fn scale(self, sx: f64, sy: f64) -> Transform {
+        self.then(&Transform { a: sx, b: 0.0, c: 0.0, d: 0.0, e: sy, f: 0.0 })
+    }
+    // Flips x, mirroring across the y axis
+    pub fn mirror_x(self) -> Transform {
+        self.scale(-1.0, 1.0)
+    }
+    // Flips y, mirroring across the x axis
+    pub fn mirror_y(self) -> Transform {
+        self.scale(1.0, -1.0)
+    }
+    pub fn then(self, next: &Transform) -> Transform {
+        Transform {
+            a: next.a * self.a + next.b * self.d,
+            b: next.a * self.b + next.b * self.e,
+            c: next.a * self.c + next.b * self.f + next.c,
+            d: next.d * self.a + next.e * self.d,
+            e: next.d * self.b + next.e * self.e,
+            f: next.d * self.c + next.e * self.f + next.f
+        }
+    }
+    // Paths mapped by a mirroring transform are reversed to keep their orientation
+    pub fn is_mirroring(&self) -> bool {
+        self.a * self.e - self.b * self.d < 0.0
+    }
+    pub fn apply(&self, point: &Point) -> Result<Point, Error> {
+        let (x, y) = (point.float_x(), point.float_y());
+        let rounded = |value: f64| {
+            let value = value.round();
+            if value.is_finite() && value.abs() <= f64::from(i32::MAX) {
+                value as i32
+            } else {
+                i32::MAX
+            }
+        };
+        Point::new(
+            rounded(self.a * x + self.b * y + self.c),
+            rounded(self.d * x + self.e * y + self.f)
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Transform;
+    use crate::primitives::Point;
+    use crate::Error;
+
+    #[test]
+    fn transform_test() {
+        let point = Point::new(3, 1).unwrap();
+        let rotated = Transform::identity().rotate(std::f64::consts::FRAC_PI_2);
+        assert_eq!(rotated.apply(&point).unwrap(), Point::new(-1, 3).unwrap());
+        let around = Transform::identity().rotate_around(std::f64::consts::PI, &Point::new(2, 2).unwrap());
+        assert_eq!(around.apply(&point).unwrap(), Point::new(1, 3).unwrap());
+        let chained = Transform::identity().translate(1.0, 0.0).scale(2.0, 3.0);
+        assert_eq!(chained.apply(&point).unwrap(), Point::new(8, 3).unwrap());
+        let rounded = Transform::identity().scale(0.5, 0.5);
+        assert_eq!(rounded.apply(&point).unwrap(), Point::new(2, 1).unwrap());
+        assert!(!rotated.is_mirroring());
+        assert!(Transform::identity().mirror_x().is_mirroring());
+        assert!(!Transform::identity().mirror_x().mirror_y().is_mirroring());
+        let huge = Transform::identity().scale(1e7, 1.0);
+        match huge.apply(&point) {
+            Err(Error::OutOfBoundsError(_)) => {},
+            _ => panic!("Out of bounds error expected")
+        }
+    }
+}
@@ -2,6 +2,7 @@ use std::ops;
 use std::cmp::Ordering;
 use std::hash::{Hash, Hasher};
 use super::integer_decode::integer_decode;
+use crate::error::Error;
 
 
 #[derive(PartialEq, PartialOrd, Debug, Clone, Copy)]
@@ -15,8 +16,14 @@ impl Pseudoangle {
     pub const DOWN: Pseudoangle = Pseudoangle{value: 2.0};
     // for use in ranges
     pub const STOP: Pseudoangle = Pseudoangle{value: 4.0};
-    pub fn new(value: f64) -> Pseudoangle {
-        if value.is_nan() { panic!("NaN value not allowed for Pseudoangle"); }
+    pub fn new(value: f64) -> Result<Pseudoangle, Error> {
+        if value.is_nan() {
+            return Err(Error::NotANumberError("pseudoangle".to_string()));
+        }
+        Ok(Pseudoangle::trimmed(value))
+    }
+    // Sums and differences of pseudoangles are never NaN
+    fn trimmed(value: f64) -> Pseudoangle {
         let trimmed = if value < 0f64 {
             value % Pseudoangle::MOD + Pseudoangle::MOD
         } else {
@@ -39,7 +46,7 @@ impl Pseudoangle {
 
     }
     pub fn reverse(&self) -> Pseudoangle {
-        self + &Pseudoangle::new(2.0)
+        self + &Pseudoangle::DOWN
     }
 }
 
@@ -61,14 +68,14 @@ impl<'a, 'b> ops::Add<&'b Pseudoangle> for &'a Pseudoangle{
     type Output = Pseudoangle;
     fn add (self, rhs: &'b Pseudoangle) -> Pseudoangle {
         let value = self.value + rhs.value;
-        Pseudoangle::new(value)
+        Pseudoangle::trimmed(value)
     }
 }
 impl<'a, 'b> ops::Sub<&'b Pseudoangle> for &'a Pseudoangle{
     type Output = Pseudoangle;
     fn sub (self, rhs: &'b Pseudoangle) -> Pseudoangle {
         let value = self.value - rhs.value;
-        Pseudoangle::new(value)
+        Pseudoangle::trimmed(value)
     }
 }
 impl Eq for Pseudoangle {}
@@ -85,22 +92,22 @@ impl Ord for Pseudoangle {
 }
 #[test]
 fn pseudoangle_test() {
-    let pseudo1p = Pseudoangle::new(1.0);
-    let pseudo2p = Pseudoangle::new(2.0);
+    let pseudo1p = Pseudoangle::new(1.0).unwrap();
+    let pseudo2p = Pseudoangle::new(2.0).unwrap();
     assert_eq!(pseudo1p.to_float(), 1.0);
-    assert_eq!(&pseudo1p + &pseudo2p, Pseudoangle::new(3.0));
-    assert_eq!(&pseudo1p - &pseudo2p, Pseudoangle::new(3.0));
-    assert_eq!(&pseudo2p - &pseudo1p, Pseudoangle::new(1.0));
+    assert_eq!(&pseudo1p + &pseudo2p, Pseudoangle::new(3.0).unwrap());
+    assert_eq!(&pseudo1p - &pseudo2p, Pseudoangle::new(3.0).unwrap());
+    assert_eq!(&pseudo2p - &pseudo1p, Pseudoangle::new(1.0).unwrap());
     assert!(&pseudo1p < &pseudo2p);
     assert!(&pseudo2p > &pseudo1p);
 }
 #[test]
 fn integer_decode_test() {
-    let pseudo0 = Pseudoangle::new(0f64);
-    let pseudo_0 = Pseudoangle::new(-0f64);
-    let pseudo_1 = Pseudoangle::new(1f64);
-    let pseudo_1dot1 = Pseudoangle::new(1.1f64);
-    let pseudo_2 = Pseudoangle::new(2.0);
+    let pseudo0 = Pseudoangle::new(0f64).unwrap();
+    let pseudo_0 = Pseudoangle::new(-0f64).unwrap();
+    let pseudo_1 = Pseudoangle::new(1f64).unwrap();
+    let pseudo_1dot1 = Pseudoangle::new(1.1f64).unwrap();
+    let pseudo_2 = Pseudoangle::new(2.0).unwrap();
 
     assert_eq!(pseudo0.integer_decode(), (0, 0, 0));
     assert_eq!(pseudo_0.integer_decode(), (0, 0, 0));
@@ -108,3 +115,10 @@ fn integer_decode_test() {
     assert_eq!(pseudo_1dot1.integer_decode(), (4953959590107546, -52, 1));
     assert_eq!(pseudo_2.integer_decode(), (4503599627370496, -51, 1));
 }
+#[test]
+fn not_a_number_test() {
+    match Pseudoangle::new(std::f64::NAN) {
+        Err(Error::NotANumberError(parameters)) => assert_eq!(parameters, "pseudoangle"),
+        other => panic!("Expected NotANumberError, got {:?}", other)
+    }
+}
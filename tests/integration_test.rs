@@ -1,5 +1,5 @@
 extern crate snipper;
-use snipper::{Snipper, Path, Polygon};
+use snipper::{Snipper, Solution, PathSink, Error, Path, PathDirection, Point, Coordinate, Polygon, Position, Bounds, Shape, Transform, Relation, Location, Dimension, Membership, IntersectionAlgorithm, IntersectionKind, Operand, Operation, Queue};
 use crate::test_helper::{test_result, test_result_and_structure};
 
 mod test_helper;
@@ -42,6 +42,25 @@ fn xor_test() {
     test_result_and_structure(result, &expected, Some(&structure));
 }
 #[test]
+fn canonical_xor_test() {
+    let big = test_helper::get_polygon("left");
+    let cross = test_helper::get_polygon("right");
+    let result = Snipper::xor(big.clone(), cross.clone()).unwrap().canonical();
+    let expected = vec![
+        vec![
+            (-15, 0), (-5, -10), (0, -5), (5, -10), (15, 0), (5, 10), (0, 5), (-5, 10)
+        ],
+        vec![
+            (-5, 0), (0, 5), (5, 0), (0, -5)
+        ],
+    ];
+    let structure = vec![None, Some(0)];
+    test_result_and_structure(result, &expected, Some(&structure));
+    let forward = Snipper::xor(big.clone(), cross.clone()).unwrap().canonical().polygon().unwrap();
+    let backward = Snipper::xor(cross, big).unwrap().canonical().polygon().unwrap();
+    assert!(forward == backward);
+}
+#[test]
 fn difference_test() {
     let big = test_helper::get_polygon("left");
     let cross = test_helper::get_polygon("right");
@@ -100,4 +119,586 @@ fn simple_graph_test() {
     ];
     let structure = vec![None, Some(0), Some(0), Some(0), Some(0), Some(0)];
     test_result_and_structure(result, &expected, Some(&structure));
-}
\ No newline at end of file
+}
+#[test]
+fn clip_to_grid_test() {
+    let diamond = test_helper::get_polygon("left");
+    let tiles = vec![
+        Bounds::new(-10, -20, 0, 0),
+        Bounds::new(-10, 0, 0, 20),
+        Bounds::new(0, -20, 10, 0),
+        Bounds::new(0, 0, 10, 20),
+        Bounds::new(100, 100, 110, 110)
+    ];
+    let solutions = Snipper::clip_to_grid(&diamond, &tiles);
+    assert_eq!(solutions.len(), tiles.len());
+    let areas: Vec<f64> = solutions.into_iter().map(|solution| {
+        solution.unwrap().polygon().unwrap().area()
+    }).collect();
+    assert_eq!(areas, vec![87.5, 12.5, 87.5, 12.5, 0.0]);
+}
+#[test]
+fn batch_test() {
+    let left = test_helper::get_polygon("left");
+    let right = test_helper::get_polygon("right");
+    let operands = vec![(left.clone(), right.clone()), (right, left)];
+    let solutions = Snipper::batch(operands, Snipper::difference);
+    let areas: Vec<f64> = solutions.into_iter().map(|solution| {
+        solution.unwrap().polygon().unwrap().area()
+    }).collect();
+    assert_eq!(areas, vec![150.0, 150.0]);
+}
+#[test]
+fn disjoint_operands_test() {
+    fn rectangle(left: i32, top: i32, right: i32, bottom: i32) -> Path {
+        Path::new(&vec![
+            Point::new(left, top).unwrap(),
+            Point::new(right, top).unwrap(),
+            Point::new(right, bottom).unwrap(),
+            Point::new(left, bottom).unwrap()
+        ])
+    }
+    let frame = Snipper::normalize(vec![rectangle(0, 0, 10, 10), rectangle(2, 2, 8, 8)]).unwrap().polygon().unwrap();
+    let square = Snipper::normalize(vec![rectangle(20, 0, 30, 10)]).unwrap().polygon().unwrap();
+    let area = |solution: Result<Solution, Error>| solution.unwrap().polygon().unwrap().area();
+    assert_eq!(area(Snipper::union(frame.clone(), square.clone())), 164.0);
+    assert_eq!(area(Snipper::xor(frame.clone(), square.clone())), 164.0);
+    assert_eq!(area(Snipper::difference(frame.clone(), square.clone())), 64.0);
+    assert_eq!(area(Snipper::difference(square.clone(), frame.clone())), 100.0);
+    assert!(Snipper::intersection(frame, square).unwrap().paths().unwrap().is_empty());
+
+    let horizontal = Snipper::normalize(vec![
+        rectangle(0, 0, 30, 5), rectangle(0, 25, 30, 30), rectangle(12, 12, 18, 18)
+    ]).unwrap().polygon().unwrap();
+    let vertical = Snipper::normalize(vec![
+        rectangle(0, 0, 5, 30), rectangle(25, 0, 30, 30)
+    ]).unwrap().polygon().unwrap();
+    let union = Snipper::union(horizontal, vertical).unwrap().polygon().unwrap();
+    assert_eq!(union.area(), 536.0);
+    let parents: Vec<Option<usize>> = union.structure().iter().map(|record| record.parent()).collect();
+    assert_eq!(parents, vec![None, Some(0), Some(1)]);
+
+    // Apart from each other, non-convex, holed and even crossing operands are
+    // only normalized each on its own
+    let path = |points: &[(i32, i32)]| Path::new(&points.iter().map(|&(x, y)| Point::new(x, y).unwrap()).collect());
+    let frame = Snipper::normalize(vec![rectangle(0, 0, 10, 10), rectangle(2, 2, 8, 8)]).unwrap().polygon().unwrap();
+    let ell = unsafe { Polygon::flat(vec![path(&[(20, 0), (30, 0), (30, 4), (24, 4), (24, 10), (20, 10)])]).unwrap() };
+    let bowtie = unsafe { Polygon::flat(vec![path(&[(40, 0), (50, 10), (50, 0), (40, 10)])]).unwrap() };
+    let union = Snipper::union(frame.clone(), ell.clone()).unwrap().polygon().unwrap();
+    assert_eq!((union.paths().len(), union.area()), (3, 64.0 + 64.0));
+    let parents: Vec<Option<usize>> = union.structure().iter().map(|record| record.parent()).collect();
+    assert_eq!(parents.iter().filter(|parent| parent.is_some()).count(), 1);
+    assert!(Snipper::difference(frame.clone(), ell.clone()).unwrap().polygon().unwrap() == frame);
+    assert!(Snipper::intersection(frame.clone(), ell).unwrap().paths().unwrap().is_empty());
+    let xor = Snipper::xor(bowtie, frame).unwrap().polygon().unwrap();
+    assert_eq!((xor.paths().len(), xor.area()), (3, 50.0 + 64.0));
+}
+#[test]
+fn many_paths_partition_test() {
+    // Just the paths, without the nesting a polygon would work out for them
+    struct Paths { paths: Vec<Path>, bounds: Bounds }
+    impl Shape for Paths {
+        fn position(&self, _point: &Point) -> Position { Position::Unknown }
+        fn bounds(&self) -> Option<&Bounds> { Some(&self.bounds) }
+        fn paths(&self) -> Vec<&Path> { self.paths.iter().collect() }
+    }
+    // Squares of the subject on even columns, of the clipping on odd ones,
+    // none of them touching
+    let square = |column: i32, row: i32| Path::new(&vec![
+        Point::new(column * 10, row * 10).unwrap(),
+        Point::new(column * 10 + 8, row * 10).unwrap(),
+        Point::new(column * 10 + 8, row * 10 + 8).unwrap(),
+        Point::new(column * 10, row * 10 + 8).unwrap()
+    ]);
+    let grid = |parity: i32| Paths {
+        paths: (0..200).flat_map(|row| (0..200).filter(move |column| column % 2 == parity).map(move |column| square(column, row))).collect(),
+        bounds: Bounds::new(0, 0, 1998, 1998)
+    };
+    assert_eq!(Snipper::perform_operation(grid(0), grid(1), &Operation::UNION).unwrap().without_cleanup().paths().unwrap().len(), 40000);
+    assert!(Snipper::perform_operation(grid(0), grid(1), &Operation::INTERSECTION).unwrap().paths().unwrap().is_empty());
+}
+#[test]
+fn cleanup_test() {
+    let square = |left: i32| Path::new(&vec![
+        Point::new(left, 0).unwrap(),
+        Point::new(left + 10, 0).unwrap(),
+        Point::new(left + 10, 10).unwrap(),
+        Point::new(left, 10).unwrap()
+    ]);
+    let a = Snipper::normalize(vec![square(0)]).unwrap().polygon().unwrap();
+    let b = Snipper::normalize(vec![square(10)]).unwrap().polygon().unwrap();
+    let raw = Snipper::union(a.clone(), b.clone()).unwrap().without_cleanup().paths().unwrap();
+    assert_eq!(raw.len(), 1);
+    assert_eq!(raw[0].points().len(), 6);
+    let cleaned = Snipper::union(a, b).unwrap().paths().unwrap();
+    assert_eq!(cleaned.len(), 1);
+    assert_eq!(cleaned[0].points().len(), 4);
+    assert_eq!(cleaned[0].area(), 200.0);
+}
+#[test]
+fn split_pinches_test() {
+    let path = |points: Vec<(i32, i32)>| Path::new(
+        &points.into_iter().map(|(x, y)| Point::new(x, y).unwrap()).collect()
+    );
+    let bow = path(vec![(-10, -10), (10, 10), (10, -10), (-10, 10)]);
+    let pinched = Snipper::normalize(vec![bow.clone()]).unwrap().polygon().unwrap();
+    assert_eq!(pinched.paths().len(), 1);
+    let split = Snipper::normalize(vec![bow]).unwrap().split_pinches().polygon().unwrap();
+    assert_eq!(split.paths().len(), 2);
+    assert_eq!(split.area(), 200.0);
+    let parents: Vec<Option<usize>> = split.structure().iter().map(|record| record.parent()).collect();
+    assert_eq!(parents, vec![None, None]);
+}
+#[test]
+fn strict_test() {
+    let path = |points: Vec<(i32, i32)>| Path::new(
+        &points.into_iter().map(|(x, y)| Point::new(x, y).unwrap()).collect()
+    );
+    let bow = path(vec![(-10, -10), (10, 10), (10, -10), (-10, 10)]);
+    let polygon = Snipper::normalize(vec![bow]).unwrap().strict().polygon().unwrap();
+    assert_eq!(polygon.paths().len(), 2);
+    assert!(polygon.paths().iter().all(|path| path.direction() == PathDirection::Clockwise));
+    let square = Snipper::normalize(vec![path(vec![(0, 0), (10, 0), (10, 10), (0, 10)])]).unwrap().polygon().unwrap();
+    let diamond = Snipper::normalize(vec![path(vec![(0, 5), (5, 2), (10, 5), (5, 8)])]).unwrap().polygon().unwrap();
+    let polygon = Snipper::difference(square, diamond).unwrap().strict().polygon().unwrap();
+    assert_eq!(polygon.paths().len(), 2);
+    assert_eq!(polygon.area(), 70.0);
+    let parents: Vec<Option<usize>> = polygon.structure().iter().map(|record| record.parent()).collect();
+    assert_eq!(parents, vec![None, None]);
+}
+#[test]
+fn transform_test() {
+    let path = |points: Vec<(i32, i32)>| Path::new(
+        &points.into_iter().map(|(x, y)| Point::new(x, y).unwrap()).collect()
+    );
+    let outer = path(vec![(0, 0), (10, 0), (10, 10), (0, 10)]);
+    let hole = path(vec![(2, 2), (4, 2), (4, 4), (2, 4)]);
+    let polygon = Snipper::normalize(vec![outer, hole]).unwrap().polygon().unwrap();
+    let mirror = Transform::identity().mirror_x().translate(20.0, 0.0);
+    let mirrored = polygon.transform(&mirror).unwrap();
+    assert!(mirrored.structure() == polygon.structure());
+    let directions = |polygon: &Polygon| -> Vec<PathDirection> {
+        polygon.paths().iter().map(|path| path.direction()).collect()
+    };
+    assert_eq!(directions(&mirrored), directions(&polygon));
+    assert_eq!(mirrored.area(), 96.0);
+    assert_eq!(mirrored.bounds().unwrap(), &Bounds::new(0, 10, 10, 20));
+    assert_eq!(mirrored.centroid(), Some(Point::new(15, 5).unwrap()));
+
+    let other = Snipper::normalize(vec![path(vec![(5, 5), (15, 5), (15, 15), (5, 15)])]).unwrap().polygon().unwrap();
+    let shift = Transform::identity().translate(100.0, 100.0);
+    let shifted = Snipper::union(polygon.clone(), other.clone()).unwrap().transform(&shift).unwrap().polygon().unwrap();
+    let expected = Snipper::union(polygon.transform(&shift).unwrap(), other.transform(&shift).unwrap())
+        .unwrap().canonical().polygon().unwrap();
+    assert!(shifted.canonical() == expected);
+
+    let huge = Transform::identity().scale(1e7, 1e7);
+    match polygon.transform(&huge) {
+        Err(Error::OutOfBoundsError(_)) => {},
+        _ => panic!("Out of bounds error expected")
+    }
+}
+
+#[test]
+fn distance_test() {
+    let path = |points: Vec<(i32, i32)>| Path::new(
+        &points.into_iter().map(|(x, y)| Point::new(x, y).unwrap()).collect()
+    );
+    let square = |x: i32, y: i32, side: i32| path(vec![(x, y), (x + side, y), (x + side, y + side), (x, y + side)]);
+    let frame = Snipper::normalize(vec![square(0, 0, 10), square(2, 2, 6)]).unwrap().polygon().unwrap();
+    let island = Snipper::normalize(vec![square(4, 4, 2)]).unwrap().polygon().unwrap();
+    assert_eq!(frame.distance(&island), 2.0);
+    assert_eq!(frame.distance_to_point(&Point::new(5, 5).unwrap()), 3.0);
+    let moved = island.transform(&Transform::identity().translate(5.0, 0.0)).unwrap();
+    assert_eq!(frame.distance(&moved), 0.0);
+    let union = Snipper::union(frame.clone(), moved).unwrap().polygon().unwrap();
+    assert_eq!(union.distance(&island), 2.0);
+    assert_eq!(frame.paths()[0].closest_point(&Point::new(13, 5).unwrap()), Some((10.0, 5.0)));
+}
+#[test]
+fn predicate_test() {
+    let path = |points: Vec<(i32, i32)>| Path::new(
+        &points.into_iter().map(|(x, y)| Point::new(x, y).unwrap()).collect()
+    );
+    let square = |x: i32, y: i32, side: i32| path(vec![(x, y), (x + side, y), (x + side, y + side), (x, y + side)]);
+    let polygon = |paths: Vec<Path>| Snipper::normalize(paths).unwrap().polygon().unwrap();
+    let base = polygon(vec![square(0, 0, 10)]);
+    let inner = polygon(vec![square(0, 2, 4)]);
+    let overlapping = polygon(vec![square(5, 5, 10)]);
+    let corner = polygon(vec![square(10, 10, 5)]);
+    let tip = polygon(vec![path(vec![(10, 5), (15, 0), (15, 10)])]);
+    let framed = polygon(vec![square(-10, -10, 30), square(-1, -1, 12)]);
+
+    assert_eq!(Snipper::relate(&base, &base.clone()).unwrap().relation(), Relation::Identical);
+    assert_eq!(Snipper::relate(&base, &inner).unwrap().relation(), Relation::Contains);
+    assert_eq!(Snipper::relate(&inner, &base).unwrap().relation(), Relation::Contained);
+    assert_eq!(Snipper::relate(&base, &overlapping).unwrap().relation(), Relation::PartlyContained);
+    assert_eq!(Snipper::relate(&base, &corner).unwrap().relation(), Relation::Touching);
+    assert_eq!(Snipper::relate(&base, &tip).unwrap().relation(), Relation::Touching);
+    assert_eq!(Snipper::relate(&base, &framed).unwrap().relation(), Relation::Unrelated);
+
+    assert!(Snipper::contains(&base, &inner).unwrap() && Snipper::within(&inner, &base).unwrap());
+    assert!(!Snipper::contains(&inner, &base).unwrap() && !Snipper::contains(&base, &overlapping).unwrap());
+    assert!(Snipper::overlaps(&base, &overlapping).unwrap() && !Snipper::overlaps(&base, &inner).unwrap());
+    assert!(Snipper::touches(&base, &corner).unwrap() && !Snipper::touches(&base, &overlapping).unwrap());
+    assert!(Snipper::intersects(&base, &tip).unwrap() && Snipper::intersects(&base, &inner).unwrap());
+    assert!(Snipper::disjoint(&base, &framed).unwrap() && !Snipper::disjoint(&base, &corner).unwrap());
+    assert!(Snipper::intersects(&base, &base.paths()[0]).unwrap());
+}
+#[test]
+fn intersection_matrix_test() {
+    let path = |points: Vec<(i32, i32)>| Path::new(
+        &points.into_iter().map(|(x, y)| Point::new(x, y).unwrap()).collect()
+    );
+    let square = |x: i32, y: i32, side: i32| path(vec![(x, y), (x + side, y), (x + side, y + side), (x, y + side)]);
+    let polygon = |paths: Vec<Path>| Snipper::normalize(paths).unwrap().polygon().unwrap();
+    let base = polygon(vec![square(0, 0, 10)]);
+    let matrix = |other: &Polygon| Snipper::relate(&base, other).unwrap().to_string();
+    assert_eq!(matrix(&base.clone()), "2FFF1FFF2");
+    assert_eq!(matrix(&polygon(vec![square(2, 2, 4)])), "212FF1FF2");
+    assert_eq!(matrix(&polygon(vec![square(0, 2, 4)])), "212F11FF2");
+    assert_eq!(matrix(&polygon(vec![square(5, 5, 10)])), "212101212");
+    assert_eq!(matrix(&polygon(vec![square(10, 10, 5)])), "FF2F01212");
+    assert_eq!(matrix(&polygon(vec![square(10, 2, 5)])), "FF2F11212");
+    assert_eq!(matrix(&polygon(vec![square(20, 0, 5)])), "FF2FF1212");
+    let framed = polygon(vec![square(-10, -10, 30), square(0, 0, 10)]);
+    assert_eq!(matrix(&framed), "FF2F1F212");
+    let inner = polygon(vec![square(2, 2, 4)]);
+    let transposed = Snipper::relate(&inner, &base).unwrap();
+    assert!(transposed == Snipper::relate(&base, &inner).unwrap().transpose());
+    assert!(transposed.matches("T*F**F***"));
+    assert_eq!(transposed.get(Location::Boundary, Location::Interior), Dimension::One);
+}
+#[test]
+fn overlay_test() {
+    let path = |points: Vec<(i32, i32)>| Path::new(
+        &points.into_iter().map(|(x, y)| Point::new(x, y).unwrap()).collect()
+    );
+    let square = |x: i32, y: i32, side: i32| path(vec![(x, y), (x + side, y), (x + side, y + side), (x, y + side)]);
+    let polygon = |paths: Vec<Path>| Snipper::normalize(paths).unwrap().polygon().unwrap();
+    let frame = polygon(vec![square(0, 0, 10), square(3, 3, 4)]);
+    let bar = polygon(vec![path(vec![(-2, 4), (12, 4), (12, 6), (-2, 6)])]);
+    let faces = Snipper::overlay(frame.clone(), bar.clone()).unwrap();
+    let area = |membership: Membership| -> f64 {
+        faces.iter().filter(|(_, label)| *label == membership).map(|(face, _)| face.area()).sum()
+    };
+    let count = |membership: Membership| faces.iter().filter(|(_, label)| *label == membership).count();
+    assert_eq!(area(Membership::Both), 12.0);
+    assert_eq!(area(Membership::Subject), 72.0);
+    assert_eq!(area(Membership::Clipping), 16.0);
+    assert_eq!(count(Membership::Both), 2);
+    assert_eq!(count(Membership::Subject), 2);
+    assert_eq!(count(Membership::Clipping), 3);
+    assert!(faces.iter().all(|(face, _)| face.structure().iter().all(|record| record.level() < 2)));
+}
+#[test]
+fn overlay_shared_edges_test() {
+    let path = |points: Vec<(i32, i32)>| Path::new(
+        &points.into_iter().map(|(x, y)| Point::new(x, y).unwrap()).collect()
+    );
+    let square = |x: i32, y: i32, side: i32| path(vec![(x, y), (x + side, y), (x + side, y + side), (x, y + side)]);
+    let polygon = |paths: Vec<Path>| Snipper::normalize(paths).unwrap().polygon().unwrap();
+    let cases = vec![
+        // Touching along a part of an edge only
+        (polygon(vec![square(0, 0, 8)]), polygon(vec![path(vec![(8, 2), (12, 2), (12, 6), (8, 6)])])),
+        (polygon(vec![square(0, 0, 10), square(3, 3, 4)]), polygon(vec![path(vec![(-2, 4), (12, 4), (12, 6), (-2, 6)])])),
+        (polygon(vec![square(0, 0, 10)]), polygon(vec![square(5, 5, 10), square(7, 0, 2)]))
+    ];
+    let key = |point: &Point| (point.float_x() as i64, point.float_y() as i64);
+    for (subject, clipping) in cases {
+        let faces = Snipper::overlay(subject, clipping).unwrap();
+        let mut segments: Vec<(usize, (i64, i64), (i64, i64))> = Vec::new();
+        for (face, (polygon, _)) in faces.iter().enumerate() {
+            for points in polygon.paths().iter().map(Path::points) {
+                for index in 0..points.len() {
+                    segments.push((face, key(&points[index]), key(&points[(index + 1) % points.len()])));
+                }
+            }
+        }
+        // Segments of different faces overlapping along a stretch are the same
+        for (face, a, b) in &segments {
+            let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+            let along = |point: &(i64, i64)| (point.0 - a.0) * dx + (point.1 - a.1) * dy;
+            let off = |point: &(i64, i64)| (point.0 - a.0) * dy - (point.1 - a.1) * dx;
+            for (other, c, d) in &segments {
+                if face == other || off(c) != 0 || off(d) != 0 { continue; }
+                let overlap = along(c).max(along(d)).min(dx * dx + dy * dy) - along(c).min(along(d)).max(0);
+                if overlap > 0 {
+                    assert!((a, b) == (c, d) || (a, b) == (d, c), "{:?}-{:?} against {:?}-{:?}", a, b, c, d);
+                }
+            }
+        }
+    }
+}
+#[test]
+fn arrangement_test() {
+    let path = |points: Vec<(i32, i32)>| Path::new(
+        &points.into_iter().map(|(x, y)| Point::new(x, y).unwrap()).collect()
+    );
+    let square = |x: i32, y: i32, side: i32| path(vec![(x, y), (x + side, y), (x + side, y + side), (x, y + side)]);
+    let polygon = |paths: Vec<Path>| Snipper::normalize(paths).unwrap().polygon().unwrap();
+    let arrangement = IntersectionAlgorithm::arrangement(polygon(vec![square(0, 0, 10)]), polygon(vec![square(5, 5, 10)])).unwrap();
+    assert_eq!(arrangement.vertices().len(), 10);
+    assert_eq!(arrangement.half_edges().len(), 24);
+    assert_eq!(arrangement.faces().len(), 4);
+    let mut areas: Vec<f64> = arrangement.faces().iter()
+        .filter_map(|face| face.outer())
+        .map(|half_edge| arrangement.path(half_edge).area())
+        .collect();
+    areas.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(areas, vec![25.0, 75.0, 75.0]);
+    let crossing = arrangement.vertices().iter().position(|point| *point == Point::new(10, 5).unwrap()).unwrap();
+    let around: Vec<usize> = arrangement.outgoing(crossing).iter().map(|half_edge| arrangement.destination(*half_edge)).collect();
+    assert_eq!(around.len(), 4);
+    let faces: Vec<usize> = arrangement.outgoing(crossing).iter().map(|half_edge| arrangement.half_edges()[*half_edge].face()).collect();
+    assert!(faces.contains(&0) && faces.iter().all(|face| *face < 4));
+
+    let shared = IntersectionAlgorithm::arrangement(polygon(vec![square(0, 0, 10)]), polygon(vec![square(10, 0, 10)])).unwrap();
+    let both = shared.half_edges().iter().filter(|half_edge| half_edge.subject() == 1 && half_edge.clipping() == 1).count();
+    assert_eq!(both, 2);
+    assert_eq!(shared.faces().len(), 3);
+    assert_eq!(shared.faces()[0].inner().len(), 1);
+}
+#[test]
+fn intersection_points_test() {
+    let path = |points: Vec<(i32, i32)>| Path::new(
+        &points.into_iter().map(|(x, y)| Point::new(x, y).unwrap()).collect()
+    );
+    let square = |x: i32, y: i32, side: i32| path(vec![(x, y), (x + side, y), (x + side, y + side), (x, y + side)]);
+    let base = square(0, 0, 10);
+
+    let crossing = Snipper::intersection_points(&base, &square(5, 5, 10)).unwrap();
+    let points: Vec<(Point, IntersectionKind)> = crossing.iter().map(|found| (found.point().clone(), found.kind())).collect();
+    assert_eq!(points, vec![
+        (Point::new(5, 10).unwrap(), IntersectionKind::Crossing),
+        (Point::new(10, 5).unwrap(), IntersectionKind::Crossing)
+    ]);
+    let segments: Vec<(Operand, usize, usize)> = crossing[1].segments().iter()
+        .map(|id| (id.operand(), id.path(), id.segment()))
+        .collect();
+    assert_eq!(segments, vec![(Operand::Subject, 0, 1), (Operand::Clipping, 0, 0)]);
+
+    let tip = Snipper::intersection_points(&base, &path(vec![(10, 5), (15, 0), (15, 10)])).unwrap();
+    assert_eq!(tip.len(), 1);
+    assert_eq!(tip[0].kind(), IntersectionKind::Touching);
+    assert_eq!(tip[0].segments().len(), 3);
+
+    let adjacent = Snipper::intersection_points(&base, &square(10, 2, 5)).unwrap();
+    let points: Vec<(Point, IntersectionKind)> = adjacent.iter().map(|found| (found.point().clone(), found.kind())).collect();
+    assert_eq!(points, vec![
+        (Point::new(10, 2).unwrap(), IntersectionKind::Overlapping),
+        (Point::new(10, 7).unwrap(), IntersectionKind::Overlapping)
+    ]);
+
+    assert!(Snipper::intersection_points(&base, &square(20, 0, 5)).unwrap().is_empty());
+
+    // Crossed by another segment where the edges overlap
+    let crossed = Snipper::intersection_points(&base, &path(vec![(10, 2), (10, 7), (13, 4), (7, 4), (7, 1), (12, 1)])).unwrap();
+    let points: Vec<(Point, IntersectionKind)> = crossed.iter().map(|found| (found.point().clone(), found.kind())).collect();
+    assert_eq!(points, vec![
+        (Point::new(10, 1).unwrap(), IntersectionKind::Crossing),
+        (Point::new(10, 2).unwrap(), IntersectionKind::Overlapping),
+        (Point::new(10, 4).unwrap(), IntersectionKind::Crossing),
+        (Point::new(10, 7).unwrap(), IntersectionKind::Overlapping)
+    ]);
+    assert_eq!(crossed[2].segments().len(), 3);
+}
+#[test]
+fn self_intersections_test() {
+    let path = |points: Vec<(i32, i32)>| Path::new(
+        &points.into_iter().map(|(x, y)| Point::new(x, y).unwrap()).collect()
+    );
+    let square = |x: i32, y: i32, side: i32| path(vec![(x, y), (x + side, y), (x + side, y + side), (x, y + side)]);
+    assert!(Snipper::self_intersections(vec![square(0, 0, 10)]).unwrap().is_empty());
+
+    let drawing = vec![
+        path(vec![(0, 0), (10, 10), (10, 0), (0, 10)]),
+        square(20, 0, 10),
+        path(vec![(25, 10), (30, 20), (20, 20)])
+    ];
+    let found = Snipper::self_intersections(drawing).unwrap();
+    let points: Vec<(Point, IntersectionKind)> = found.iter().map(|found| (found.point().clone(), found.kind())).collect();
+    assert_eq!(points, vec![
+        (Point::new(5, 5).unwrap(), IntersectionKind::Crossing),
+        (Point::new(25, 10).unwrap(), IntersectionKind::Touching)
+    ]);
+    let segments = |index: usize| -> Vec<(usize, usize)> {
+        found[index].segments().iter().map(|id| (id.path(), id.segment())).collect()
+    };
+    assert_eq!(segments(0), vec![(0, 0), (0, 2)]);
+    assert_eq!(segments(1), vec![(1, 2), (2, 0), (2, 2)]);
+
+    let pinched = path(vec![(0, 0), (5, 0), (5, 5), (10, 5), (10, 10), (5, 10), (5, 5), (0, 5)]);
+    let found = Snipper::self_intersections(vec![pinched]).unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].kind(), IntersectionKind::Touching);
+    assert_eq!(found[0].segments().len(), 4);
+
+    let adjacent = Snipper::self_intersections(vec![square(0, 0, 10), square(10, 2, 5)]).unwrap();
+    let points: Vec<(Point, IntersectionKind)> = adjacent.iter().map(|found| (found.point().clone(), found.kind())).collect();
+    assert_eq!(points, vec![
+        (Point::new(10, 2).unwrap(), IntersectionKind::Overlapping),
+        (Point::new(10, 7).unwrap(), IntersectionKind::Overlapping)
+    ]);
+}
+#[test]
+fn snap_rounded_test() {
+    let path = |points: Vec<(i32, i32)>| Path::new(
+        &points.into_iter().map(|(x, y)| Point::new(x, y).unwrap()).collect()
+    );
+    let square = |x: i32, y: i32, side: i32| path(vec![(x, y), (x + side, y), (x + side, y + side), (x, y + side)]);
+    let merged = Snipper::normalize(vec![square(0, 0, 10), square(11, 0, 10)]).unwrap()
+        .snap_rounded(2).unwrap()
+        .polygon().unwrap();
+    assert_eq!(merged.paths().len(), 1);
+    assert_eq!(merged.area(), 240.0);
+
+    let drawing = vec![square(0, 0, 30), path(vec![(5, 5), (25, 6), (5, 8)]), path(vec![(12, 9), (20, 20), (14, 24)])];
+    for tolerance in 1..4 {
+        let polygon = Snipper::normalize(drawing.clone()).unwrap()
+            .snap_rounded(tolerance).unwrap()
+            .polygon().unwrap();
+        for vertex in polygon.paths().iter().flat_map(|path| path.points().iter()) {
+            for path in polygon.paths() {
+                for index in 0..path.points().len() {
+                    let segment = path.segment_at(index).unwrap();
+                    let distance = segment.distance_to_point(vertex);
+                    assert!(segment.is_endpoint(vertex) || distance < 1e-9 || distance >= tolerance as f64);
+                }
+            }
+        }
+    }
+    assert!(Snipper::normalize(drawing).unwrap().snap_rounded(0).is_err());
+
+    // Pixels at the bounds have centres past them
+    let (max, min) = (Coordinate::MAX, Coordinate::MIN);
+    let corners = Snipper::normalize(vec![square(max - 10, max - 10, 10), square(min, min, 10)]).unwrap()
+        .snap_rounded(3).unwrap()
+        .polygon().unwrap();
+    let points: Vec<Vec<(i32, i32)>> = corners.paths().iter()
+        .map(|path| path.points().iter().map(|point| (point.float_x() as i32, point.float_y() as i32)).collect())
+        .collect();
+    assert_eq!(points, vec![
+        vec![(max, max - 10), (max, max), (max - 10, max), (max - 10, max - 10)],
+        vec![(min + 10, min), (min + 10, min + 10), (min, min + 10), (min, min)]
+    ]);
+}
+#[test]
+fn sliver_test() {
+    let path = |points: Vec<(i32, i32)>| Path::new(
+        &points.into_iter().map(|(x, y)| Point::new(x, y).unwrap()).collect()
+    );
+    let rectangle = |x: i32, y: i32, width: i32, height: i32| path(vec![(x, y), (x + width, y), (x + width, y + height), (x, y + height)]);
+    let polygon = |paths: Vec<Path>| Snipper::normalize(paths).unwrap().polygon().unwrap();
+    let base = polygon(vec![rectangle(0, 0, 100, 100)]);
+    let shifted = polygon(vec![rectangle(1, 0, 100, 100)]);
+    let strip = || Snipper::difference(base.clone(), shifted.clone()).unwrap();
+    assert_eq!(strip().polygon().unwrap().area(), 100.0);
+    assert_eq!(strip().min_area(150.0).polygon().unwrap().area(), 0.0);
+    assert_eq!(strip().min_area(50.0).polygon().unwrap().area(), 100.0);
+    assert_eq!(strip().min_width(2.0).polygon().unwrap().area(), 0.0);
+    assert_eq!(strip().min_width(0.5).polygon().unwrap().area(), 100.0);
+    assert_eq!(strip().max_thinness(10.0).polygon().unwrap().area(), 0.0);
+    assert_eq!(strip().max_thinness(40.0).polygon().unwrap().area(), 100.0);
+
+    let slot = polygon(vec![rectangle(20, 20, 60, 1)]);
+    let window = polygon(vec![rectangle(20, 40, 50, 50)]);
+    let holes = Snipper::union(slot, window).unwrap().polygon().unwrap();
+    let filtered = Snipper::difference(base.clone(), holes.clone()).unwrap().min_width(2.0).polygon().unwrap();
+    assert_eq!(filtered.paths().len(), 2);
+    assert_eq!(filtered.area(), 7500.0);
+    let paths = Snipper::difference(base, holes).unwrap().min_width(2.0).paths().unwrap();
+    assert_eq!(paths.len(), 2);
+}
+#[test]
+fn streamed_operands_test() {
+    let ring = |x: i32, y: i32, side: i32| vec![(x, y), (x + side, y), (x + side, y + side), (x, y + side)].into_iter();
+    let mut queue = Queue::new();
+    queue.add_coordinates(ring(0, 0, 10), Operand::Subject).unwrap();
+    queue.add_coordinates(ring(2, 2, 6), Operand::Subject).unwrap();
+    // Failing part of the way, nothing of it is swept
+    let invalid = vec![(20, 0), (30, 0), (Coordinate::MAX + 1, 10)];
+    assert!(queue.add_coordinates(invalid, Operand::Subject).is_err());
+    queue.add_ring(ring(5, 5, 10).map(|(x, y)| Point::new(x, y).unwrap()), Operand::Clipping).unwrap();
+    let streamed = Snipper::perform_queued(queue, &Operation::UNION).unwrap().canonical().paths().unwrap();
+
+    let path = |points: std::vec::IntoIter<(i32, i32)>| Path::new(&points.map(|(x, y)| Point::new(x, y).unwrap()).collect());
+    let subject = Snipper::normalize(vec![path(ring(0, 0, 10)), path(ring(2, 2, 6))]).unwrap().polygon().unwrap();
+    let clipping = Snipper::normalize(vec![path(ring(5, 5, 10))]).unwrap().polygon().unwrap();
+    let materialized = Snipper::union(subject, clipping).unwrap().canonical().paths().unwrap();
+    let points = |paths: Vec<Path>| -> Vec<Vec<Point>> { paths.iter().map(|path| path.points().clone()).collect() };
+    assert_eq!(points(streamed), points(materialized));
+}
+struct RightmostSink {
+    paths: Vec<Path>,
+    rightmost: Vec<i32>
+}
+impl PathSink for RightmostSink {
+    fn push_path(&mut self, path: Path) -> Result<(), Error> {
+        self.rightmost.push(path.bounds().unwrap().right().to_int());
+        self.paths.push(path);
+        Ok(())
+    }
+}
+#[test]
+fn path_sink_test() {
+    let square = |x: i32, y: i32, side: i32| Path::new(&vec![
+        Point::new(x, y).unwrap(), Point::new(x + side, y).unwrap(),
+        Point::new(x + side, y + side).unwrap(), Point::new(x, y + side).unwrap()
+    ]);
+    let subject = Snipper::normalize(vec![square(0, 0, 10), square(20, 0, 10), square(40, 0, 10)]).unwrap().polygon().unwrap();
+    let clipping = Snipper::normalize(vec![square(5, 0, 20), square(42, 2, 6)]).unwrap().polygon().unwrap();
+    let mut sink = RightmostSink { paths: Vec::new(), rightmost: Vec::new() };
+    Snipper::perform_operation_into(subject.clone(), clipping.clone(), &Operation::XOR, &mut sink).unwrap();
+    // Paths come out as the sweep line passes them
+    assert_eq!(sink.rightmost, vec![25, 30, 48, 50]);
+
+    let canonical = |paths: Vec<Path>| -> Vec<Vec<Point>> {
+        let mut paths: Vec<Path> = paths.into_iter().map(Path::canonical).collect();
+        paths.sort_by(Path::canonical_order);
+        paths.iter().map(|path| path.points().clone()).collect()
+    };
+    let materialized = Snipper::xor(subject.clone(), clipping.clone()).unwrap().paths().unwrap();
+    assert_eq!(canonical(sink.paths), canonical(materialized));
+
+    let mut collected: Vec<Path> = Vec::new();
+    Snipper::perform_operation_into(subject.clone(), clipping.clone(), &Operation::UNION, &mut collected).unwrap();
+    assert_eq!(collected.len(), 2);
+
+    struct FailingSink {}
+    impl PathSink for FailingSink {
+        fn push_path(&mut self, _: Path) -> Result<(), Error> {
+            Err(Error::SinkError("full".to_string()))
+        }
+    }
+    let result = Snipper::perform_operation_into(subject, clipping, &Operation::UNION, &mut FailingSink {});
+    assert!(matches!(result, Err(Error::SinkError(_))));
+}
+#[test]
+fn command_line_test() {
+    let directory = std::env::temp_dir().join(format!("snipper_command_line_test_{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let subject = directory.join("subject.wkt");
+    let clipping = directory.join("clipping.geojson");
+    std::fs::write(&subject, "POLYGON ((0 0, 10 0, 10 10, 0 10, 0 0), (2 2, 2 4, 4 4, 4 2, 2 2))").unwrap();
+    std::fs::write(&clipping, r#"{"type": "Polygon", "coordinates": [[[5, 5], [15, 5], [15, 15], [5, 15], [5, 5]]]}"#).unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_snipper"))
+        .args(["union", subject.to_str().unwrap(), clipping.to_str().unwrap(), "--canonical"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "MULTIPOLYGON (((0 0, 10 0, 10 5, 15 5, 15 15, 5 15, 5 10, 0 10, 0 0), (2 2, 2 4, 4 4, 4 2, 2 2)))\n"
+    );
+    let statistics = String::from_utf8(output.stderr).unwrap();
+    assert!(statistics.contains("area: 171"));
+    assert!(statistics.contains("path 1: level 1, parent 0"));
+
+    let failed = std::process::Command::new(env!("CARGO_BIN_EXE_snipper"))
+        .args(["union", subject.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(!failed.status.success());
+    std::fs::remove_dir_all(&directory).unwrap();
+}
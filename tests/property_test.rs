@@ -0,0 +1,196 @@
+extern crate snipper;
+extern crate rand;
+use snipper::{AbstractPoint, Bounds, Point, Path, Polygon, Position, Shape, Snipper, Operation};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+static NUM_CASES: u64 = 100;
+static SIDE: i32 = 1000;
+static NUM_SAMPLES: usize = 200;
+static MARGIN: f64 = 2.0;
+
+fn random_paths(rng: &mut StdRng) -> Vec<Path> {
+    let num_paths = rng.gen_range(1, 3);
+    (0..num_paths).map(|_| {
+        let num_points = rng.gen_range(3, 9);
+        let points: Vec<Point> = (0..num_points).map(|_| {
+            Point::new(rng.gen_range(0, SIDE), rng.gen_range(0, SIDE)).unwrap()
+        }).collect();
+        Path::new(&points)
+    }).collect()
+}
+fn random_polygon(rng: &mut StdRng) -> Polygon {
+    Snipper::normalize(random_paths(rng)).unwrap().polygon().unwrap()
+}
+fn segments(polygon: &Polygon) -> Vec<(Point, Point)> {
+    polygon.paths().iter().flat_map(|path| {
+        let points = path.points();
+        (0..points.len()).map(move |index| {
+            (points[index].clone(), points[(index + 1) % points.len()].clone())
+        })
+    }).collect()
+}
+fn distance_to_segment(point: &Point, start: &Point, end: &Point) -> f64 {
+    let (px, py) = (point.float_x(), point.float_y());
+    let (sx, sy) = (start.float_x(), start.float_y());
+    let (dx, dy) = (end.float_x() - sx, end.float_y() - sy);
+    let squared = dx * dx + dy * dy;
+    let parameter = if squared == 0.0 {
+        0.0
+    } else {
+        (((px - sx) * dx + (py - sy) * dy) / squared).max(0.0).min(1.0)
+    };
+    let (cx, cy) = (sx + parameter * dx, sy + parameter * dy);
+    ((px - cx) * (px - cx) + (py - cy) * (py - cy)).sqrt()
+}
+fn perimeter(polygon: &Polygon) -> f64 {
+    segments(polygon).iter().map(|(start, end)| {
+        (end.float_x() - start.float_x()).hypot(end.float_y() - start.float_y())
+    }).sum()
+}
+// Output vertices are rounded to the grid, so points too close
+// to any input edge can't be reliably classified
+fn sample_points(rng: &mut StdRng, operands: &[&Polygon]) -> Vec<Point> {
+    let edges: Vec<(Point, Point)> = operands.iter().flat_map(|polygon| segments(polygon)).collect();
+    let mut samples = Vec::new();
+    while samples.len() < NUM_SAMPLES {
+        let point = Point::new(rng.gen_range(0, SIDE), rng.gen_range(0, SIDE)).unwrap();
+        let clear = edges.iter().all(|(start, end)| {
+            distance_to_segment(&point, start, end) > MARGIN
+        });
+        if clear {
+            samples.push(point);
+        }
+    }
+    samples
+}
+fn is_in(polygon: &Polygon, point: &Point) -> bool {
+    polygon.position(point) == Position::In
+}
+fn area_tolerance(operands: &[&Polygon]) -> f64 {
+    operands.iter().map(|polygon| perimeter(polygon)).sum::<f64>() + 1.0
+}
+
+#[test]
+fn inclusion_exclusion_test() {
+    for seed in 0..NUM_CASES {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let a = random_polygon(&mut rng);
+        let b = random_polygon(&mut rng);
+        let union = Snipper::union(a.clone(), b.clone()).unwrap().polygon().unwrap();
+        let intersection = Snipper::intersection(a.clone(), b.clone()).unwrap().polygon().unwrap();
+        let lhs = union.area() + intersection.area();
+        let rhs = a.area() + b.area();
+        let tolerance = area_tolerance(&[&a, &b]);
+        assert!((lhs - rhs).abs() <= tolerance, "Seed {}: {} != {}", seed, lhs, rhs);
+    }
+}
+#[test]
+fn xor_as_differences_test() {
+    for seed in 0..NUM_CASES {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let a = random_polygon(&mut rng);
+        let b = random_polygon(&mut rng);
+        let xor = Snipper::xor(a.clone(), b.clone()).unwrap().polygon().unwrap();
+        let a_minus_b = Snipper::difference(a.clone(), b.clone()).unwrap().polygon().unwrap();
+        let b_minus_a = Snipper::difference(b.clone(), a.clone()).unwrap().polygon().unwrap();
+        let union = Snipper::union(a_minus_b, b_minus_a).unwrap().polygon().unwrap();
+        let tolerance = area_tolerance(&[&a, &b]);
+        assert!(
+            (xor.area() - union.area()).abs() <= tolerance,
+            "Seed {}: {} != {}", seed, xor.area(), union.area()
+        );
+        for point in sample_points(&mut rng, &[&a, &b]) {
+            assert_eq!(is_in(&xor, &point), is_in(&union, &point), "Seed {} at {}", seed, point.inspect());
+        }
+    }
+}
+#[test]
+fn pointwise_operations_test() {
+    for seed in 0..NUM_CASES {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let a = random_polygon(&mut rng);
+        let b = random_polygon(&mut rng);
+        let union = Snipper::union(a.clone(), b.clone()).unwrap().polygon().unwrap();
+        let intersection = Snipper::intersection(a.clone(), b.clone()).unwrap().polygon().unwrap();
+        let difference = Snipper::difference(a.clone(), b.clone()).unwrap().polygon().unwrap();
+        let xor = Snipper::xor(a.clone(), b.clone()).unwrap().polygon().unwrap();
+        for point in sample_points(&mut rng, &[&a, &b]) {
+            let in_a = is_in(&a, &point);
+            let in_b = is_in(&b, &point);
+            let at = point.inspect();
+            assert_eq!(is_in(&union, &point), in_a || in_b, "Union, seed {} at {}", seed, at);
+            assert_eq!(is_in(&intersection, &point), in_a && in_b, "Intersection, seed {} at {}", seed, at);
+            assert_eq!(is_in(&difference, &point), in_a && !in_b, "Difference, seed {} at {}", seed, at);
+            assert_eq!(is_in(&xor, &point), in_a != in_b, "Xor, seed {} at {}", seed, at);
+        }
+    }
+}
+#[test]
+fn normalize_idempotence_test() {
+    for seed in 0..NUM_CASES {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let once = random_polygon(&mut rng);
+        let twice = Snipper::normalize(once.paths().clone()).unwrap().polygon().unwrap();
+        assert!(
+            (once.area() - twice.area()).abs() < 0.001,
+            "Seed {}: {} != {}", seed, once.area(), twice.area()
+        );
+        assert_eq!(once.paths().len(), twice.paths().len(), "Seed {}", seed);
+        for point in sample_points(&mut rng, &[&once]) {
+            assert_eq!(is_in(&once, &point), is_in(&twice, &point), "Seed {} at {}", seed, point.inspect());
+        }
+    }
+}
+#[test]
+fn clip_to_bounds_test() {
+    for seed in 0..NUM_CASES {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let polygon = random_polygon(&mut rng);
+        let (x1, x2) = (rng.gen_range(0, SIDE), rng.gen_range(0, SIDE));
+        let (y1, y2) = (rng.gen_range(0, SIDE), rng.gen_range(0, SIDE));
+        let bounds = Bounds::new(y1.min(y2), x1.min(x2), y1.max(y2), x1.max(x2));
+        let corners = vec![
+            Point::new(x1.min(x2), y1.min(y2)).unwrap(),
+            Point::new(x1.max(x2), y1.min(y2)).unwrap(),
+            Point::new(x1.max(x2), y1.max(y2)).unwrap(),
+            Point::new(x1.min(x2), y1.max(y2)).unwrap()
+        ];
+        let rectangle = Snipper::normalize(vec![Path::new(&corners)]).unwrap().polygon().unwrap();
+        let clipped = Snipper::clip_to_bounds(&polygon, &bounds).unwrap().polygon().unwrap();
+        let intersection = Snipper::intersection(polygon.clone(), rectangle.clone()).unwrap().polygon().unwrap();
+        let tolerance = area_tolerance(&[&polygon, &rectangle]);
+        assert!(
+            (clipped.area() - intersection.area()).abs() <= tolerance,
+            "Seed {}: {} != {}", seed, clipped.area(), intersection.area()
+        );
+        for point in sample_points(&mut rng, &[&polygon, &rectangle]) {
+            assert_eq!(is_in(&clipped, &point), is_in(&intersection, &point), "Seed {} at {}", seed, point.inspect());
+        }
+    }
+}
+#[test]
+fn split_pinches_area_test() {
+    // Coarse enough for rings to touch themselves often
+    let small_polygon = |rng: &mut StdRng| {
+        let num_points = rng.gen_range(3, 9);
+        let points: Vec<Point> = (0..num_points).map(|_| {
+            Point::new(rng.gen_range(0, 20), rng.gen_range(0, 20)).unwrap()
+        }).collect();
+        Snipper::normalize(vec![Path::new(&points)]).unwrap().polygon().unwrap()
+    };
+    let operations = [&Operation::UNION, &Operation::INTERSECTION, &Operation::DIFFERENCE, &Operation::XOR];
+    for seed in 0..NUM_CASES * 5 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let a = small_polygon(&mut rng);
+        let b = small_polygon(&mut rng);
+        for operation in operations {
+            let whole = Snipper::perform_operation(a.clone(), b.clone(), operation).unwrap().polygon().unwrap();
+            let split = Snipper::perform_operation(a.clone(), b.clone(), operation).unwrap().split_pinches().polygon().unwrap();
+            assert!(
+                (whole.area() - split.area()).abs() < 0.001,
+                "Seed {}: {} != {}", seed, whole.area(), split.area()
+            );
+        }
+    }
+}